@@ -0,0 +1,35 @@
+-- One row per markdown file, with the fingerprint used to skip unchanged files.
+CREATE TABLE files (
+    path TEXT PRIMARY KEY NOT NULL, -- Vault-relative
+    modified_ns INTEGER, -- Nanoseconds since the epoch; NULL if the platform has no mtime
+    size INTEGER NOT NULL,
+    hash TEXT NOT NULL, -- SHA-256 of the content
+    stem TEXT NOT NULL,
+    note_id TEXT NOT NULL, -- UUID, or the stem for notes without frontmatter
+    title TEXT,
+    -- Lower-cased names links resolve against
+    stem_key TEXT NOT NULL,
+    title_key TEXT
+);
+
+CREATE INDEX files_note_id ON files (note_id);
+CREATE INDEX files_stem_key ON files (stem_key);
+CREATE INDEX files_title_key ON files (title_key);
+
+CREATE TABLE links (
+    source_path TEXT NOT NULL REFERENCES files (path) ON DELETE CASCADE,
+    position INTEGER NOT NULL, -- Order within the file
+    raw TEXT NOT NULL, -- As written inside `[[...]]`
+    target_key TEXT NOT NULL, -- Lower-cased target without heading or alias
+    PRIMARY KEY (source_path, position)
+);
+
+CREATE INDEX links_target_key ON links (target_key);
+
+CREATE TABLE tags (
+    path TEXT NOT NULL REFERENCES files (path) ON DELETE CASCADE,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    PRIMARY KEY (path, tag)
+);
+
+CREATE INDEX tags_tag ON tags (tag);
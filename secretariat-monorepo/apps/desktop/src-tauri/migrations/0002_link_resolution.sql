@@ -0,0 +1,17 @@
+-- Links now resolve without accents and through paths and frontmatter aliases.
+-- Keys written by 0001 were only lower-cased, so every file is indexed again.
+DELETE FROM files;
+
+ALTER TABLE files ADD COLUMN path_key TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX files_path_key ON files (path_key);
+
+CREATE TABLE aliases (
+    path TEXT NOT NULL REFERENCES files (path) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    alias TEXT NOT NULL,
+    alias_key TEXT NOT NULL,
+    PRIMARY KEY (path, position)
+);
+
+CREATE INDEX aliases_alias_key ON aliases (alias_key);
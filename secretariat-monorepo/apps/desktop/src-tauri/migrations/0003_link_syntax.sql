@@ -0,0 +1,6 @@
+-- Markdown links and embeds are indexed next to wikilinks; code is no longer
+-- scanned for links, so every file is indexed again.
+DELETE FROM files;
+
+ALTER TABLE links ADD COLUMN syntax TEXT NOT NULL DEFAULT 'wiki'; -- 'wiki' or 'markdown'
+ALTER TABLE links ADD COLUMN embed BOOLEAN NOT NULL DEFAULT FALSE;
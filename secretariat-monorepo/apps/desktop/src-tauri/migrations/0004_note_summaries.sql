@@ -0,0 +1,7 @@
+-- The note list reads dates and word counts from the index instead of every
+-- file, so every file is indexed again to fill them in.
+DELETE FROM files;
+
+ALTER TABLE files ADD COLUMN created_at TEXT; -- NULL for markdown without frontmatter
+ALTER TABLE files ADD COLUMN updated_at TEXT;
+ALTER TABLE files ADD COLUMN word_count INTEGER NOT NULL DEFAULT 0;
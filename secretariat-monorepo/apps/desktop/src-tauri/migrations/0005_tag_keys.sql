@@ -0,0 +1,7 @@
+-- Tags are matched like the note list matches them: `#Work` is `work`.
+-- Every file is indexed again to fill in the keys.
+DELETE FROM files;
+
+ALTER TABLE tags ADD COLUMN tag_key TEXT NOT NULL DEFAULT '';
+
+CREATE INDEX tags_tag_key ON tags (tag_key);
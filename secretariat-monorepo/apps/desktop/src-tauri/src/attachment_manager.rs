@@ -0,0 +1,149 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use sha2::{Digest, Sha256};
+use crate::graph_engine::extract_links;
+use crate::trash_manager::move_to_trash;
+use crate::filename_policy::sanitize_file_stem;
+use crate::vault_manager::{is_hidden, list_markdown_files, save_file, save_json, VaultPath, VaultState};
+use crate::wikilink::WikiLink;
+
+/// Images, PDFs and audio referenced by notes.
+pub const ATTACHMENTS_DIR: &str = "_attachments";
+/// Content hash -> vault-relative path, so the same file is only stored once.
+const ATTACHMENT_INDEX: &str = ".secretariat/attachments.json";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AttachmentInfo {
+    pub path: String,
+    pub file_name: String,
+    pub embed: String, // `![[file]]`, ready to paste into a note
+    pub hash: String,
+    pub deduplicated: bool, // The same content was already in the vault
+}
+
+/// Copies a file picked or dropped in the app into `_attachments/`. Only the path
+/// crosses IPC; the file is read here. Identical content is stored once.
+#[tauri::command]
+pub fn import_attachment(state: State<'_, VaultState>, source_path: PathBuf) -> Result<AttachmentInfo, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    import_attachment_file(&vault_path, &source_path)
+}
+
+/// Attachments no note links to. They are only moved to the trash when `dry_run` is `false`.
+#[tauri::command]
+pub fn cleanup_attachments(state: State<'_, VaultState>, dry_run: Option<bool>) -> Result<Vec<String>, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let unreferenced = find_unreferenced_attachments(&vault_path)?;
+
+    if !dry_run.unwrap_or(true) {
+        let mut index = load_index(&vault_path)?;
+        for relative in &unreferenced {
+            move_to_trash(&vault_path, &VaultPath::new(&vault_path, relative)?)?;
+            index.retain(|_, path| path != relative);
+        }
+        save_json(&vault_path.join(ATTACHMENT_INDEX), &index)?;
+    }
+
+    Ok(unreferenced)
+}
+
+pub fn import_attachment_file(vault_path: &Path, source: &Path) -> Result<AttachmentInfo, String> {
+    let file_name = source.file_name().and_then(|n| n.to_str()).ok_or("Source has no file name")?;
+    let data = fs::read(source).map_err(|e| format!("{}: {}", source.display(), e))?;
+    store_attachment(vault_path, file_name, &data)
+}
+
+/// Stores `data` under `_attachments/`; importing the same content again returns the existing file.
+pub fn store_attachment(vault_path: &Path, file_name: &str, data: &[u8]) -> Result<AttachmentInfo, String> {
+    let hash = format!("{:x}", Sha256::digest(data));
+    let mut index = load_index(vault_path)?;
+
+    if let Some(existing) = index.get(&hash) {
+        let existing_path = VaultPath::new(vault_path, existing)?;
+        if existing_path.as_path().exists() {
+            return Ok(attachment_info(&existing_path, hash, true));
+        }
+    }
+
+    // Keep the original name readable; the hash prefix avoids clashes between
+    // different files that share a name
+    let original = Path::new(file_name);
+    let stem = original.file_stem().and_then(|s| s.to_str()).unwrap_or("attachment");
+    let stored_name = match original.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}-{}.{}", sanitize_file_stem(stem), &hash[..8], sanitize_file_stem(extension)),
+        None => format!("{}-{}", sanitize_file_stem(stem), &hash[..8]),
+    };
+
+    fs::create_dir_all(vault_path.join(ATTACHMENTS_DIR)).map_err(|e| e.to_string())?;
+    let attachment_path = VaultPath::new(vault_path, &format!("{}/{}", ATTACHMENTS_DIR, stored_name))?;
+    save_file(attachment_path.as_path(), data)?;
+
+    index.insert(hash.clone(), attachment_path.relative().to_string_lossy().to_string());
+    save_json(&vault_path.join(ATTACHMENT_INDEX), &index)?;
+
+    Ok(attachment_info(&attachment_path, hash, false))
+}
+
+/// Files under `_attachments/` that no note references, by file name or path.
+pub fn find_unreferenced_attachments(vault_path: &Path) -> Result<Vec<String>, String> {
+    let attachments_dir = vault_path.join(ATTACHMENTS_DIR);
+    if !attachments_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut referenced: HashSet<String> = HashSet::new();
+    for path in list_markdown_files(vault_path)? {
+        if let Ok(content) = fs::read_to_string(&path) {
+            referenced.extend(referenced_files(&content));
+        }
+    }
+
+    let pattern = attachments_dir.join("**/*");
+    let pattern_str = pattern.to_str().ok_or("Invalid vault path")?;
+    let mut unreferenced = Vec::new();
+    for path in glob::glob(pattern_str).map_err(|e| e.to_string())?.flatten() {
+        if !path.is_file() {
+            continue;
+        }
+        let attachment = VaultPath::from_full_path(vault_path, &path)?;
+        if is_hidden(attachment.relative()) {
+            continue;
+        }
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if !referenced.contains(&file_name) {
+            unreferenced.push(attachment.relative().to_string_lossy().to_string());
+        }
+    }
+    unreferenced.sort();
+    Ok(unreferenced)
+}
+
+/// Lower-cased file names a note points at, through `[[...]]` or `](...)` links outside of code.
+pub fn referenced_files(content: &str) -> HashSet<String> {
+    extract_links(content)
+        .into_iter()
+        .map(|raw| WikiLink::parse(&raw).target)
+        .filter_map(|target| Path::new(&target).file_name().map(|n| n.to_string_lossy().to_lowercase()))
+        .collect()
+}
+
+fn attachment_info(path: &VaultPath, hash: String, deduplicated: bool) -> AttachmentInfo {
+    let file_name = path.as_path().file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    AttachmentInfo {
+        path: path.relative().to_string_lossy().to_string(),
+        embed: format!("![[{}]]", file_name),
+        file_name,
+        hash,
+        deduplicated,
+    }
+}
+
+fn load_index(vault_path: &Path) -> Result<HashMap<String, String>, String> {
+    match fs::read_to_string(vault_path.join(ATTACHMENT_INDEX)) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        Err(_) => Ok(HashMap::new()),
+    }
+}
@@ -0,0 +1,350 @@
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use chrono::Utc;
+use crate::folder_manager::path_in_folder;
+use crate::frontmatter::{apply_patch, Document};
+use crate::graph_engine::{sync_index, GraphCache, GraphState};
+use crate::trash_manager::{move_to_trash, restore_from_trash};
+use crate::vault_manager::{
+    normalize_tag, paginate_notes, relink_note, save_json, save_note, touch_metadata,
+    ListNotesQuery, VaultPath, VaultState,
+};
+
+/// One file per applied batch, used to revert it.
+const JOURNAL_DIR: &str = ".secretariat/journal";
+
+/// Notes a batch applies to: explicit ids, or everything matching a `list_notes` filter.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchSelection {
+    Ids { ids: Vec<String> },
+    Query { query: ListNotesQuery }, // `cursor` and `limit` are ignored
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchOperation {
+    AddTag { tag: String },
+    RemoveTag { tag: String },
+    SetField { key: String, value: serde_json::Value }, // `null` removes the field
+    MoveToFolder { folder: String },
+    Delete,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BatchChange {
+    pub path: String,
+    pub new_path: Option<String>, // Set for moves
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BatchFailure {
+    pub path: String, // Or the id that could not be found
+    pub error: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BatchReport {
+    pub batch_id: Option<String>, // Journal entry to pass to `undo_batch`; None for dry runs
+    pub changes: Vec<BatchChange>,
+    pub unchanged: usize, // Already in the requested state
+    pub failed: Vec<BatchFailure>,
+}
+
+/// What is needed to revert one file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub path: String,
+    pub new_path: Option<String>,
+    pub previous: Option<String>, // File content before an edit
+    pub version: Option<u64>, // Version the batch wrote; a later edit blocks the undo
+    pub trash_entry_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchJournal {
+    pub batch_id: String,
+    pub created_at: String,
+    pub operation: BatchOperation,
+    pub entries: Vec<JournalEntry>,
+    pub undone_at: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct UndoReport {
+    pub restored: Vec<String>,
+    pub failed: Vec<BatchFailure>,
+}
+
+/// Applies `operation` to every selected note. Nothing is written unless `dry_run`
+/// is `false`; the dry run returns the same report as a preview.
+#[tauri::command]
+pub async fn batch_notes(
+    app: AppHandle,
+    state: State<'_, VaultState>,
+    graph_state: State<'_, GraphState>,
+    selection: BatchSelection,
+    operation: BatchOperation,
+    dry_run: Option<bool>
+) -> Result<BatchReport, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+
+    let mut failed = Vec::new();
+    let paths = match selection {
+        BatchSelection::Ids { ids } => {
+            let mut paths = Vec::new();
+            for id in ids {
+                match state.find_note(&id) {
+                    Ok((_, path)) => paths.push(path),
+                    Err(error) => failed.push(BatchFailure { path: id, error }),
+                }
+            }
+            paths
+        },
+        BatchSelection::Query { query } => {
+            // Same summaries as `list_notes`, so a query selects what the sidebar shows
+            let _store = sync_index(&app, &vault_path, &graph_state).await?;
+            let cache = graph_state.cache.lock().map_err(|e| e.to_string())?;
+            select_by_query(&vault_path, &cache, query)?
+        },
+    };
+
+    let mut report = run_batch(&vault_path, &paths, &operation, dry_run.unwrap_or(true))?;
+    failed.append(&mut report.failed);
+    report.failed = failed;
+    Ok(report)
+}
+
+/// Reverts an applied batch. Files edited again since are left alone and reported.
+#[tauri::command]
+pub fn undo_batch(state: State<'_, VaultState>, batch_id: String) -> Result<UndoReport, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    revert_batch(&vault_path, &batch_id)
+}
+
+/// Applied batches, newest first.
+#[tauri::command]
+pub fn list_batches(state: State<'_, VaultState>) -> Result<Vec<BatchJournal>, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let Ok(entries) = fs::read_dir(vault_path.join(JOURNAL_DIR)) else { return Ok(Vec::new()) };
+
+    let mut journals: Vec<BatchJournal> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    journals.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(journals)
+}
+
+fn select_by_query(vault_path: &Path, cache: &GraphCache, query: ListNotesQuery) -> Result<Vec<VaultPath>, String> {
+    let summaries = cache.files.iter().map(|(relative, entry)| entry.summary(relative)).collect();
+
+    let query = ListNotesQuery { cursor: None, limit: Some(usize::MAX), ..query };
+    paginate_notes(summaries, &query)?
+        .notes
+        .iter()
+        .map(|note| VaultPath::new(vault_path, &note.path))
+        .collect()
+}
+
+pub fn run_batch(vault_path: &Path, paths: &[VaultPath], operation: &BatchOperation, dry_run: bool) -> Result<BatchReport, String> {
+    let mut journal = BatchJournal {
+        batch_id: format!("{}-{}", Utc::now().format("%Y%m%dT%H%M%S"), &uuid::Uuid::new_v4().to_string()[..8]),
+        created_at: Utc::now().to_rfc3339(),
+        operation: operation.clone(),
+        entries: Vec::new(),
+        undone_at: None,
+    };
+    let mut report = BatchReport { batch_id: None, changes: Vec::new(), unchanged: 0, failed: Vec::new() };
+
+    for path in paths {
+        let relative = path.relative().to_string_lossy().to_string();
+        let result = if dry_run {
+            plan_change(vault_path, path, operation).map(|planned| planned.map(|(change, _)| (change, None)))
+        } else {
+            apply_change(vault_path, path, operation)
+        };
+
+        match result {
+            Ok(Some((change, entry))) => {
+                report.changes.push(change);
+                if let Some(entry) = entry {
+                    journal.entries.push(entry);
+                    // Written after every file, so a crash mid-batch can still be undone
+                    save_json(&journal_file(vault_path, &journal.batch_id)?, &journal)?;
+                }
+            },
+            Ok(None) => report.unchanged += 1,
+            Err(error) => report.failed.push(BatchFailure { path: relative, error }),
+        }
+    }
+
+    if !journal.entries.is_empty() {
+        report.batch_id = Some(journal.batch_id);
+    }
+    Ok(report)
+}
+
+/// What `operation` would do to one file: the change, and for edits the new content.
+/// `None` when the note is already in the requested state.
+fn plan_change(
+    vault_path: &Path,
+    path: &VaultPath,
+    operation: &BatchOperation
+) -> Result<Option<(BatchChange, Option<String>)>, String> {
+    let relative = path.relative().to_string_lossy().to_string();
+    let change = |new_path: Option<String>| BatchChange { path: relative.clone(), new_path };
+
+    match operation {
+        BatchOperation::MoveToFolder { folder } => {
+            let file_name = path.as_path().file_name().and_then(|n| n.to_str()).ok_or("Invalid note path")?;
+            let target = path_in_folder(vault_path, folder, file_name)?;
+            if target.relative() == path.relative() {
+                return Ok(None);
+            }
+            if target.as_path().exists() {
+                return Err(format!("A note named {} already exists in {}", file_name, folder));
+            }
+            Ok(Some((change(Some(target.relative().to_string_lossy().to_string())), None)))
+        },
+        BatchOperation::Delete => Ok(Some((change(None), None))),
+        edit => {
+            let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+            let mut document = Document::parse(&raw)?;
+            let before = document.fields().clone();
+            let mut metadata = document.metadata()?;
+
+            match edit {
+                BatchOperation::AddTag { tag } => {
+                    let tag = tag.trim().trim_start_matches('#').to_string();
+                    if tag.is_empty() {
+                        return Err("Tag is empty".to_string());
+                    }
+                    if !metadata.tags.iter().any(|t| normalize_tag(t) == normalize_tag(&tag)) {
+                        metadata.tags.push(tag);
+                    }
+                },
+                BatchOperation::RemoveTag { tag } => metadata.tags.retain(|t| normalize_tag(t) != normalize_tag(tag)),
+                _ => {},
+            }
+            document.set_metadata(&metadata)?;
+            if let BatchOperation::SetField { key, value } = edit {
+                let mut patch = serde_json::Map::new();
+                patch.insert(key.clone(), value.clone());
+                apply_patch(&mut document, &patch)?;
+            }
+
+            if document.fields() == &before {
+                return Ok(None);
+            }
+            let mut metadata = document.metadata()?;
+            touch_metadata(&mut metadata);
+            document.set_metadata(&metadata)?;
+            Ok(Some((change(None), Some(document.render()?))))
+        },
+    }
+}
+
+fn apply_change(
+    vault_path: &Path,
+    path: &VaultPath,
+    operation: &BatchOperation
+) -> Result<Option<(BatchChange, Option<JournalEntry>)>, String> {
+    let Some((change, content)) = plan_change(vault_path, path, operation)? else { return Ok(None) };
+    let mut entry = JournalEntry {
+        path: change.path.clone(),
+        new_path: change.new_path.clone(),
+        previous: None,
+        version: None,
+        trash_entry_id: None,
+    };
+
+    match (operation, content) {
+        (BatchOperation::Delete, _) => {
+            entry.trash_entry_id = Some(move_to_trash(vault_path, path)?.entry_id);
+        },
+        (BatchOperation::MoveToFolder { .. }, _) => {
+            let target = VaultPath::new(vault_path, change.new_path.as_deref().ok_or("Missing target")?)?;
+            if let Some(parent) = target.as_path().parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::rename(path, &target).map_err(|e| e.to_string())?;
+            relink_note(vault_path, path, &target, &[])?;
+        },
+        (_, Some(content)) => {
+            entry.previous = Some(fs::read_to_string(path).map_err(|e| e.to_string())?);
+            entry.version = Some(Document::parse(&content)?.metadata()?.version);
+            save_note(vault_path, path, content)?;
+        },
+        (_, None) => return Ok(None),
+    }
+    Ok(Some((change, Some(entry))))
+}
+
+pub fn revert_batch(vault_path: &Path, batch_id: &str) -> Result<UndoReport, String> {
+    let journal_path = journal_file(vault_path, batch_id)?;
+    let mut journal: BatchJournal = serde_json::from_str(
+        &fs::read_to_string(&journal_path).map_err(|_| format!("Batch not found: {}", batch_id))?
+    ).map_err(|e| e.to_string())?;
+    if journal.undone_at.is_some() {
+        return Err(format!("Batch {} was already undone", batch_id));
+    }
+
+    let mut report = UndoReport { restored: Vec::new(), failed: Vec::new() };
+    for entry in journal.entries.iter().rev() {
+        match revert_entry(vault_path, entry) {
+            Ok(()) => report.restored.push(entry.path.clone()),
+            Err(error) => report.failed.push(BatchFailure { path: entry.path.clone(), error }),
+        }
+    }
+
+    journal.undone_at = Some(Utc::now().to_rfc3339());
+    save_json(&journal_path, &journal)?;
+    Ok(report)
+}
+
+fn revert_entry(vault_path: &Path, entry: &JournalEntry) -> Result<(), String> {
+    if let Some(trash_entry_id) = &entry.trash_entry_id {
+        restore_from_trash(vault_path, trash_entry_id)?;
+        return Ok(());
+    }
+
+    if let Some(new_path) = &entry.new_path {
+        let moved = VaultPath::new(vault_path, new_path)?;
+        let original = VaultPath::new(vault_path, &entry.path)?;
+        if original.as_path().exists() {
+            return Err(format!("A file already exists at {}", entry.path));
+        }
+        if let Some(parent) = original.as_path().parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::rename(&moved, &original).map_err(|e| e.to_string())?;
+        return relink_note(vault_path, &moved, &original, &[]).map(|_| ());
+    }
+
+    let (Some(previous), Some(version)) = (&entry.previous, entry.version) else { return Ok(()) };
+    let note_path = VaultPath::new(vault_path, &entry.path)?;
+    let current = Document::parse(&fs::read_to_string(&note_path).map_err(|e| e.to_string())?)?.metadata()?;
+    if current.version != version {
+        return Err(format!("Edited since the batch (version {} is now {})", version, current.version));
+    }
+
+    // The old content comes back as a new version, so sync sees the undo too
+    let mut document = Document::parse(previous)?;
+    let mut metadata = document.metadata()?;
+    metadata.version = current.version;
+    touch_metadata(&mut metadata);
+    document.set_metadata(&metadata)?;
+    save_note(vault_path, &note_path, document.render()?)
+}
+
+fn journal_file(vault_path: &Path, batch_id: &str) -> Result<std::path::PathBuf, String> {
+    // Batch ids come from the webview: they must stay a single path segment
+    if batch_id.is_empty() || batch_id.contains(['/', '\\']) || batch_id.starts_with('.') {
+        return Err(format!("Invalid batch id: {}", batch_id));
+    }
+    Ok(vault_path.join(JOURNAL_DIR).join(format!("{}.json", batch_id)))
+}
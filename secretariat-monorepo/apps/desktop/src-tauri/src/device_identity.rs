@@ -0,0 +1,80 @@
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use chrono::Utc;
+use crate::vault_manager::save_json;
+
+/// Stored in the app data dir: the identity belongs to this installation, not to a vault.
+pub const DEVICE_FILE: &str = "device.json";
+
+/// Written by code paths that never load an identity (tests, CLI tools); the app refuses to start without one.
+const UNKNOWN_DEVICE_ID: &str = "unknown-device";
+
+static DEVICE: OnceLock<DeviceIdentity> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeviceIdentity {
+    pub device_id: String,
+    pub name: String, // Shown in conflict logs, e.g. "work-laptop"
+    pub platform: String,
+    pub created_at: String, // ISO 8601
+}
+
+/// Identity of this installation, as written into notes and the sync index.
+#[tauri::command]
+pub fn get_device_info() -> Result<DeviceIdentity, String> {
+    DEVICE.get().cloned().ok_or_else(|| "Device identity is not loaded".to_string())
+}
+
+/// Loads the identity, generating it on first run, and makes it the current device.
+pub fn init_device(app_data_dir: &Path) -> Result<DeviceIdentity, String> {
+    let identity = load_or_create_identity(&app_data_dir.join(DEVICE_FILE))?;
+    Ok(DEVICE.get_or_init(|| identity).clone())
+}
+
+pub fn load_or_create_identity(path: &Path) -> Result<DeviceIdentity, String> {
+    if let Ok(json) = fs::read_to_string(path) {
+        match serde_json::from_str(&json) {
+            Ok(identity) => return Ok(identity),
+            Err(e) => {
+                // Keep the damaged file for inspection and register this installation again
+                let backup = path.with_extension(format!("json.corrupt-{}", Utc::now().format("%Y%m%dT%H%M%S")));
+                fs::rename(path, &backup).map_err(|e| e.to_string())?;
+                println!("Unreadable device file {} ({}), moved to {}", path.display(), e, backup.display());
+            }
+        }
+    }
+
+    let identity = DeviceIdentity {
+        device_id: uuid::Uuid::new_v4().to_string(),
+        name: host_name().unwrap_or_else(|| "Secretariat Desktop".to_string()),
+        platform: std::env::consts::OS.to_string(),
+        created_at: Utc::now().to_rfc3339(),
+    };
+    save_json(path, &identity)?;
+    println!("Registered new device {} ({})", identity.name, identity.device_id);
+    Ok(identity)
+}
+
+/// `device_id` written into frontmatter, tombstones and the sync index.
+pub fn current_device_id() -> String {
+    DEVICE.get().map(|d| d.device_id.clone()).unwrap_or_else(|| UNKNOWN_DEVICE_ID.to_string())
+}
+
+pub fn current_device_name() -> String {
+    DEVICE.get().map(|d| d.name.clone()).unwrap_or_else(|| UNKNOWN_DEVICE_ID.to_string())
+}
+
+fn host_name() -> Option<String> {
+    let name = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .or_else(|| {
+            let output = std::process::Command::new("hostname").output().ok()?;
+            String::from_utf8(output.stdout).ok()
+        })?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+use crate::folder_manager::path_in_folder;
+use crate::vault_config::load_config;
+use crate::vault_manager::VaultPath;
+
+/// Device names Windows refuses as file names, with or without an extension.
+const WINDOWS_RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Leaves room for folders, a collision suffix and `.md` within Windows' 260 character paths.
+const MAX_STEM_BYTES: usize = 120;
+const MAX_COLLISIONS: usize = 1000;
+
+/// Pure function for RUST-02 Test
+pub fn sanitize_filename(title: &str) -> String {
+    format!("{}.md", sanitize_file_stem(title))
+}
+
+/// A name that is valid on Linux, macOS and Windows alike, without extension.
+/// The title itself is kept in the note's frontmatter, so nothing is lost here.
+pub fn sanitize_file_stem(name: &str) -> String {
+    let mut stem: String = name
+        .nfc()
+        .map(|c| match c {
+            '/' | '\\' => '-',
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+
+    // Windows drops trailing dots and spaces; a leading dot hides the file
+    stem = stem.trim().trim_end_matches(['.', ' ']).trim_start_matches('.').trim_start().to_string();
+
+    if stem.len() > MAX_STEM_BYTES {
+        let mut end = MAX_STEM_BYTES;
+        while !stem.is_char_boundary(end) {
+            end -= 1;
+        }
+        stem.truncate(end);
+        stem = stem.trim_end_matches(['.', ' ']).to_string();
+    }
+
+    let device_name = stem.split('.').next().unwrap_or_default().trim_end();
+    if WINDOWS_RESERVED.iter().any(|reserved| reserved.eq_ignore_ascii_case(device_name)) {
+        stem.insert(device_name.len(), '_');
+    }
+
+    if stem.is_empty() {
+        "Untitled".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Free path for a note titled `title` in `folder`. Taken names get the vault's
+/// collision suffix (`Note (1).md`, `Note (2).md`, ...). `current` is the note being
+/// renamed, which may keep its own name.
+pub fn unique_note_path(vault_path: &Path, folder: &str, title: &str, current: Option<&VaultPath>) -> Result<VaultPath, String> {
+    let stem = sanitize_file_stem(title);
+    let mut suffix = load_config(vault_path)?.files.collision_suffix;
+    if !suffix.contains("{n}") {
+        suffix.push_str("{n}");
+    }
+
+    for n in 0..MAX_COLLISIONS {
+        let candidate = match n {
+            0 => format!("{}.md", stem),
+            n => format!("{}{}.md", stem, suffix.replace("{n}", &n.to_string())),
+        };
+        let path = path_in_folder(vault_path, folder, &candidate)?;
+        if !name_taken(&path, current) {
+            return Ok(path);
+        }
+    }
+    Err(format!("Too many notes named {}", title))
+}
+
+/// Whether another file in the same folder already has this name. Compared the way
+/// Windows and macOS do: case-insensitively and regardless of Unicode normalization.
+fn name_taken(path: &VaultPath, current: Option<&VaultPath>) -> bool {
+    let fold = |name: &str| name.nfc().collect::<String>().to_lowercase();
+    let Some(file_name) = path.as_path().file_name().map(|n| fold(&n.to_string_lossy())) else { return true };
+    let Some(dir) = path.as_path().parent() else { return true };
+    let Ok(entries) = fs::read_dir(dir) else { return false };
+
+    entries.flatten().any(|entry| {
+        fold(&entry.file_name().to_string_lossy()) == file_name
+            && current.is_none_or(|current| entry.path() != current.as_path())
+    })
+}
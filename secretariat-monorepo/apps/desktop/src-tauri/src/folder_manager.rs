@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use crate::attachment_manager::ATTACHMENTS_DIR;
+use crate::template_manager::TEMPLATES_DIR;
+use crate::vault_config::{load_config, save_config};
+use crate::filename_policy::sanitize_file_stem;
+use crate::vault_manager::{is_hidden, list_markdown_files, relink_note, relink_notes, VaultPath, VaultState};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FolderNode {
+    pub name: String,
+    pub path: String, // Vault-relative; empty for the vault root
+    pub note_count: usize, // Notes directly in this folder
+    pub total_notes: usize, // Including subfolders
+    pub children: Vec<FolderNode>,
+}
+
+#[tauri::command]
+pub fn create_folder(state: State<'_, VaultState>, path: String) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    make_folder(&vault_path, &path)
+}
+
+/// Moves a note into `folder` (empty for the vault root), keeping its file name.
+/// Links by path, including relative markdown links to and from the note, are rewritten.
+#[tauri::command]
+pub fn move_note(state: State<'_, VaultState>, filename: String, folder: String) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    move_note_to(&vault_path, &filename, &folder)
+}
+
+/// Renames the last segment of a folder, e.g. `Projects/2023` to `Projects/Archive`.
+/// Links to its notes by path are rewritten, like when moving a note.
+#[tauri::command]
+pub fn rename_folder(state: State<'_, VaultState>, path: String, new_name: String) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    rename_folder_at(&vault_path, &path, &new_name)
+}
+
+/// Nested folders of the vault with note counts, for the sidebar tree.
+#[tauri::command]
+pub fn folder_tree(state: State<'_, VaultState>) -> Result<FolderNode, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    build_folder_tree(&vault_path)
+}
+
+/// Resolves a folder for notes. App data folders are hidden and cannot hold notes.
+pub fn resolve_folder(vault_path: &Path, folder: &str) -> Result<Option<VaultPath>, String> {
+    if folder.trim_matches(['/', '\\']).is_empty() {
+        return Ok(None); // Vault root
+    }
+    let folder_path = VaultPath::new(vault_path, folder)?;
+    if is_hidden(folder_path.relative()) {
+        return Err(format!("Hidden folders cannot hold notes: {}", folder));
+    }
+    if folder_path.as_path().is_file() {
+        return Err(format!("Not a folder: {}", folder));
+    }
+    Ok(Some(folder_path))
+}
+
+/// Vault-relative path of `file_name` inside `folder`.
+pub fn path_in_folder(vault_path: &Path, folder: &str, file_name: &str) -> Result<VaultPath, String> {
+    match resolve_folder(vault_path, folder)? {
+        Some(folder_path) => {
+            let relative = folder_path.relative().join(file_name);
+            VaultPath::new(vault_path, relative.to_str().ok_or("Invalid folder path")?)
+        },
+        None => VaultPath::new(vault_path, file_name),
+    }
+}
+
+pub fn make_folder(vault_path: &Path, path: &str) -> Result<String, String> {
+    let folder_path = resolve_folder(vault_path, path)?.ok_or("Folder name is empty")?;
+    if folder_path.as_path().exists() {
+        return Err(format!("Folder already exists: {}", path));
+    }
+    fs::create_dir_all(&folder_path).map_err(|e| e.to_string())?;
+    Ok(folder_path.relative().to_string_lossy().to_string())
+}
+
+pub fn move_note_to(vault_path: &Path, filename: &str, folder: &str) -> Result<String, String> {
+    let note_path = VaultPath::new(vault_path, filename)?;
+    if !note_path.as_path().is_file() {
+        return Err(format!("Note not found: {}", filename));
+    }
+    let file_name = note_path.as_path().file_name().and_then(|n| n.to_str()).ok_or("Invalid note path")?;
+
+    let target = path_in_folder(vault_path, folder, file_name)?;
+    if target.relative() == note_path.relative() {
+        return Ok(filename.to_string());
+    }
+    if target.as_path().exists() {
+        return Err(format!("A note named {} already exists in {}", file_name, folder));
+    }
+
+    if let Some(parent) = target.as_path().parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&note_path, &target).map_err(|e| e.to_string())?;
+    let (updated_files, links_rewritten) = relink_note(vault_path, &note_path, &target, &[])?;
+    println!("Moved {}: rewrote {} links in {} notes", filename, links_rewritten, updated_files.len());
+    Ok(target.relative().to_string_lossy().to_string())
+}
+
+pub fn rename_folder_at(vault_path: &Path, path: &str, new_name: &str) -> Result<String, String> {
+    let folder_path = resolve_folder(vault_path, path)?.ok_or("Cannot rename the vault root")?;
+    if !folder_path.as_path().is_dir() {
+        return Err(format!("Folder not found: {}", path));
+    }
+    // Templates and attachments are looked up at fixed locations
+    if [TEMPLATES_DIR, ATTACHMENTS_DIR].iter().any(|reserved| folder_path.relative() == Path::new(reserved)) {
+        return Err(format!("{} is managed by Secretariat and cannot be renamed", path));
+    }
+
+    let new_name = sanitize_file_stem(new_name.trim());
+    if new_name.is_empty() || new_name.starts_with('.') {
+        return Err("Invalid folder name".to_string());
+    }
+    let new_relative = folder_path.relative().with_file_name(&new_name);
+    let new_relative = new_relative.to_str().ok_or("Invalid folder path")?.to_string();
+    let new_path = VaultPath::new(vault_path, &new_relative)?;
+    if new_path.as_path().exists() {
+        return Err(format!("Folder already exists: {}", new_relative));
+    }
+
+    let relative = |path: &Path| path.to_string_lossy().replace('\\', "/");
+    let moves: Vec<(String, String)> = list_markdown_files(vault_path)?
+        .iter()
+        .filter_map(|note| note.relative().strip_prefix(folder_path.relative()).ok().map(|inside| {
+            (relative(note.relative()), relative(&Path::new(&new_relative).join(inside)))
+        }))
+        .collect();
+
+    fs::rename(&folder_path, &new_path).map_err(|e| e.to_string())?;
+    let (updated_files, links_rewritten) = relink_notes(vault_path, &moves, &[])?;
+    println!("Renamed {}: rewrote {} links in {} notes", path, links_rewritten, updated_files.len());
+
+    // Keep daily notes landing in the folder the user knows
+    let mut config = load_config(vault_path)?;
+    if Path::new(&config.daily_notes.folder) == folder_path.relative() {
+        config.daily_notes.folder = new_relative.clone();
+        save_config(vault_path, &config)?;
+    }
+
+    Ok(new_relative)
+}
+
+pub fn build_folder_tree(vault_path: &Path) -> Result<FolderNode, String> {
+    let name = vault_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    scan_folder(vault_path, Path::new(""), name)
+}
+
+fn scan_folder(vault_path: &Path, relative: &Path, name: String) -> Result<FolderNode, String> {
+    let mut node = FolderNode {
+        name,
+        path: relative.to_string_lossy().to_string(),
+        note_count: 0,
+        total_notes: 0,
+        children: Vec::new(),
+    };
+
+    for entry in fs::read_dir(vault_path.join(relative)).map_err(|e| e.to_string())?.flatten() {
+        let entry_name = entry.file_name().to_string_lossy().to_string();
+        if entry_name.starts_with('.') {
+            continue;
+        }
+        // Symlinked folders are not followed: they may lead outside of the vault
+        let Ok(file_type) = entry.file_type() else { continue };
+        let entry_relative = relative.join(&entry_name);
+
+        if file_type.is_dir() {
+            if entry_relative == Path::new(TEMPLATES_DIR) {
+                continue;
+            }
+            let child = scan_folder(vault_path, &entry_relative, entry_name)?;
+            node.total_notes += child.total_notes;
+            node.children.push(child);
+        } else if entry_relative.extension().and_then(|e| e.to_str()) == Some("md") {
+            node.note_count += 1;
+        }
+    }
+
+    node.total_notes += node.note_count;
+    node.children.sort_by_key(|child| child.name.to_lowercase());
+    Ok(node)
+}
@@ -0,0 +1,229 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use serde_yaml::{Mapping, Value};
+use crate::vault_manager::{bump_version, save_note, touch_metadata, NoteMetadata, VaultPath, VaultState};
+
+/// Fields the app manages itself; a patch cannot set or remove them.
+const PROTECTED_FIELDS: [&str; 5] = ["id", "created_at", "updated_at", "version", "device_id"];
+
+/// One markdown file, split into its YAML frontmatter and its body.
+///
+/// This is the single view of a note's YAML used by the vault, the AI and sync.
+/// Unknown fields and key order are kept, the body is kept byte-for-byte, and
+/// frontmatter that was not changed is written back exactly as it was read.
+#[derive(Debug, Clone)]
+pub struct Document {
+    fields: Mapping,
+    body: String,
+    // Blank line between frontmatter and body; not part of what the editor shows
+    separator: String,
+    // The frontmatter block as read from disk, reused while `fields` is unchanged
+    original_head: Option<String>,
+    has_frontmatter: bool,
+}
+
+impl Document {
+    /// Parses a file. Plain markdown without frontmatter yields empty fields.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let Some((yaml, body)) = split_frontmatter(raw) else {
+            return Ok(Self {
+                fields: Mapping::new(),
+                body: raw.to_string(),
+                separator: String::new(),
+                original_head: None,
+                has_frontmatter: false,
+            });
+        };
+
+        let fields = match serde_yaml::from_str::<Value>(yaml).map_err(|e| e.to_string())? {
+            Value::Mapping(fields) => fields,
+            Value::Null => Mapping::new(), // Empty frontmatter block
+            _ => return Err("Frontmatter is not a mapping".to_string()),
+        };
+        let separator = if body.starts_with("\r\n") {
+            "\r\n"
+        } else if body.starts_with('\n') {
+            "\n"
+        } else {
+            ""
+        };
+
+        Ok(Self {
+            fields,
+            body: body.to_string(),
+            separator: separator.to_string(),
+            original_head: Some(raw[..raw.len() - body.len()].to_string()),
+            has_frontmatter: true,
+        })
+    }
+
+    /// A new note in the layout `create_note` has always written.
+    pub fn new_note(metadata: &NoteMetadata, content: &str) -> Result<Self, String> {
+        let mut document = Self {
+            fields: Mapping::new(),
+            body: format!("\n{}", content),
+            separator: "\n".to_string(),
+            original_head: None,
+            has_frontmatter: true,
+        };
+        document.set_metadata(metadata)?;
+        Ok(document)
+    }
+
+    pub fn has_frontmatter(&self) -> bool {
+        self.has_frontmatter
+    }
+
+    pub fn fields(&self) -> &Mapping {
+        &self.fields
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.fields.get(key)
+    }
+
+    /// Sets a field in place, or appends it after the existing ones.
+    pub fn set(&mut self, key: &str, value: Value) {
+        if self.fields.get(key) != Some(&value) {
+            self.fields.insert(Value::String(key.to_string()), value);
+            self.original_head = None;
+            self.has_frontmatter = true;
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if self.fields.remove(key).is_some() {
+            self.original_head = None;
+        }
+    }
+
+    /// Deserializes the fields into a typed view (`NoteMetadata`, sync metadata, ...).
+    pub fn view<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_yaml::from_value(Value::Mapping(self.fields.clone())).map_err(|e| e.to_string())
+    }
+
+    pub fn metadata(&self) -> Result<NoteMetadata, String> {
+        if !self.has_frontmatter {
+            return Err("Note has no frontmatter".to_string());
+        }
+        self.view()
+    }
+
+    /// Writes a typed view back. Only changed keys are touched; everything else,
+    /// including fields the app does not know about, stays where it was.
+    pub fn set_metadata<T: Serialize>(&mut self, metadata: &T) -> Result<(), String> {
+        let Value::Mapping(values) = serde_yaml::to_value(metadata).map_err(|e| e.to_string())? else {
+            return Err("Metadata must serialize to a mapping".to_string());
+        };
+        for (key, value) in values {
+            if let Some(key) = key.as_str() {
+                self.set(key, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// The body exactly as stored after the frontmatter.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn set_body(&mut self, body: String) {
+        self.body = body;
+    }
+
+    /// The body as the editor sees it, without the separating blank line.
+    pub fn content(&self) -> &str {
+        &self.body[self.separator.len().min(self.body.len())..]
+    }
+
+    pub fn set_content(&mut self, content: &str) {
+        self.body = format!("{}{}", self.separator, content);
+    }
+
+    pub fn render(&self) -> Result<String, String> {
+        if let Some(head) = &self.original_head {
+            return Ok(format!("{}{}", head, self.body));
+        }
+        if !self.has_frontmatter {
+            return Ok(self.body.clone());
+        }
+
+        let yaml = if self.fields.is_empty() {
+            String::new()
+        } else {
+            serde_yaml::to_string(&self.fields).map_err(|e| e.to_string())?
+        };
+        Ok(format!("---\n{}---\n{}", yaml, self.body))
+    }
+}
+
+/// Splits `---` delimited YAML frontmatter from the rest of the file.
+/// Returns `None` for plain markdown without a frontmatter block.
+pub fn split_frontmatter(raw: &str) -> Option<(&str, &str)> {
+    let rest = raw.strip_prefix("---\n").or_else(|| raw.strip_prefix("---\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let yaml = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            return Some((yaml, body));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Applies a JSON patch to a document: `null` removes a field, anything else sets it.
+/// Fields the app manages (`id`, `version`, timestamps) are rejected.
+pub fn apply_patch(document: &mut Document, patch: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+    for (key, value) in patch {
+        if PROTECTED_FIELDS.contains(&key.as_str()) {
+            return Err(format!("Field {} is managed by Secretariat", key));
+        }
+        match value {
+            serde_json::Value::Null => document.remove(key),
+            value => document.set(key, serde_yaml::to_value(value).map_err(|e| e.to_string())?),
+        }
+    }
+    Ok(())
+}
+
+/// Patches a note's frontmatter and records the change as a new version.
+pub fn patch_frontmatter(
+    vault_path: &Path,
+    note_path: &VaultPath,
+    patch: &serde_json::Map<String, serde_json::Value>,
+    expected_version: Option<u64>
+) -> Result<Mapping, String> {
+    let raw = fs::read_to_string(note_path).map_err(|e| e.to_string())?;
+    let mut document = Document::parse(&raw)?;
+
+    apply_patch(&mut document, patch)?;
+    let mut metadata = document.metadata()?;
+    match expected_version {
+        Some(expected_version) => bump_version(&mut metadata, expected_version)?,
+        None => touch_metadata(&mut metadata),
+    }
+    document.set_metadata(&metadata)?;
+
+    save_note(vault_path, note_path, document.render()?)?;
+    Ok(document.fields().clone())
+}
+
+/// Sets or removes (`null`) frontmatter fields of a note. Returns the resulting fields.
+#[tauri::command]
+pub fn update_frontmatter(
+    state: State<'_, VaultState>,
+    filename: String,
+    patch: serde_json::Map<String, serde_json::Value>,
+    expected_version: Option<u64>
+) -> Result<Mapping, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let note_path = VaultPath::new(&vault_path, &filename)?;
+    patch_frontmatter(&vault_path, &note_path, &patch, expected_version)
+}
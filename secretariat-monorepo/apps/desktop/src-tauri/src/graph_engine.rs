@@ -1,123 +1,533 @@
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use regex::Regex;
-use glob::glob;
-use tauri::State;
-use crate::vault_manager::VaultState;
+use std::time::SystemTime;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+use crate::frontmatter::{split_frontmatter, Document};
+use crate::graph_metrics::{compute_metrics, rank_notes, Metric, NodeMetrics};
+use crate::graph_store::{graph_db_file, GraphStore, IndexedNote};
+use crate::wikilink::{frontmatter_aliases, normalize_name, note_link_spans, parse_note_links, LinkResolver, LinkSyntax, NoteLink};
+use crate::vault_manager::{list_markdown_files, NoteSummary, VaultState};
+use crate::vault_watcher::WatcherState;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Note,
+    Ghost, // Linked to, but no such note exists yet
+    Attachment,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    Link, // `[[Note]]`
+    Markdown, // `[text](Note.md)`
+    Embed, // `![[Note]]` transclusion of another note
+    Attachment, // `![[file.png]]` or `![](file.png)` of a non-note file
+}
 
 #[derive(Serialize, Clone)]
 pub struct GraphNode {
-    pub id: String,
+    pub id: String, // Note UUID; the name for ghosts and attachments
     pub label: String,
+    pub path: Option<String>, // Vault-relative file of a note
     pub weight: usize, // Calculated by number of connections
+    pub kind: NodeKind,
+    pub metrics: NodeMetrics,
 }
 
 #[derive(Serialize, Clone)]
 pub struct GraphLink {
     pub source: String,
     pub target: String,
+    pub kind: LinkKind,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct GraphData {
     pub nodes: Vec<GraphNode>,
     pub links: Vec<GraphLink>,
 }
 
+/// Cheap change detection: `modified` and `size` come from a stat, `hash` is
+/// only computed when those differ, so a touched but unchanged file is not re-parsed.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Fingerprint {
+    pub modified: Option<SystemTime>,
+    pub size: u64,
+    pub hash: String,
+}
+
+/// What the graph needs from one note, kept between rebuilds.
+#[derive(Clone, Debug)]
+pub struct FileEntry {
+    pub fingerprint: Fingerprint,
+    pub stem: String,
+    pub id: String, // UUID, or the stem for notes without frontmatter
+    pub title: Option<String>,
+    pub aliases: Vec<String>, // From the `aliases:` frontmatter
+    pub links: Vec<NoteLink>,
+    pub tags: Vec<String>,
+    pub created_at: Option<String>, // None without frontmatter, like `title`
+    pub updated_at: Option<String>,
+    pub word_count: usize,
+}
+
+impl FileEntry {
+    /// The note list entry for the file at `relative`; no file is read.
+    pub fn summary(&self, relative: &str) -> NoteSummary {
+        NoteSummary {
+            id: self.title.as_ref().map(|_| self.id.clone()),
+            title: self.title.clone().unwrap_or_else(|| self.stem.clone()),
+            path: relative.to_string(),
+            tags: self.tags.clone(),
+            created_at: self.created_at.clone(),
+            updated_at: self.updated_at.clone(),
+            word_count: self.word_count,
+        }
+    }
+}
+
+/// Per-file entries of one vault, keyed by vault-relative path.
+#[derive(Default)]
+pub struct GraphCache {
+    pub vault_path: PathBuf,
+    pub files: HashMap<String, FileEntry>,
+}
+
+#[derive(Default, Debug, PartialEq)]
+pub struct RebuildStats {
+    pub parsed: usize,
+    pub reused: usize,
+    pub updated: Vec<String>, // Entries whose fingerprint changed, parsed or not
+    pub removed: Vec<String>,
+}
+
 pub struct GraphState {
-    // Cache structure could go here
-    pub last_data: Mutex<Option<GraphData>>,
+    pub last_data: Mutex<Option<(PathBuf, GraphData)>>, // With the vault it was built from; dropped whenever a vault file changes
+    pub cache: Mutex<GraphCache>,
+    // Opened on first use for the active vault; `None` while unavailable
+    pub store: tokio::sync::Mutex<Option<GraphStore>>,
 }
 
 impl GraphState {
     pub fn new() -> Self {
         Self {
             last_data: Mutex::new(None),
+            cache: Mutex::new(GraphCache::default()),
+            store: tokio::sync::Mutex::new(None),
         }
     }
 }
 
+/// The graph as of the last build, rebuilt only if files changed since.
 #[tauri::command]
-pub fn rebuild_graph(
+pub async fn get_graph(
+    app: AppHandle,
     vault_state: State<'_, VaultState>,
     graph_state: State<'_, GraphState>
 ) -> Result<GraphData, String> {
     let vault_path = vault_state.vault_path.lock().map_err(|e| e.to_string())?.clone();
-    let pattern = vault_path.join("**/*.md");
-    let pattern_str = pattern.to_str().ok_or("Invalid vault path")?;
+    // Without a watcher nothing drops stale data, so every call reconciles;
+    // unchanged files only cost a stat
+    if app.state::<WatcherState>().is_active() {
+        if let Some((built_from, data)) = graph_state.last_data.lock().map_err(|e| e.to_string())?.as_ref() {
+            if *built_from == vault_path {
+                return Ok(data.clone());
+            }
+        }
+    }
+    rebuild_graph(app, vault_state, graph_state).await
+}
 
-    let mut nodes_map: HashMap<String, usize> = HashMap::new(); // ID -> Weight
-    let mut links: HashSet<(String, String)> = HashSet::new();
-    
-    // 1. First Pass: Collect all Nodes and parse Links
-    println!("Scanning Vault at: {}", pattern_str);
-    let mut file_count = 0;
-    for entry in glob(pattern_str).map_err(|e| e.to_string())? {
-        match entry {
-            Ok(path) => {
-                file_count += 1;
-                let file_stem = path.file_stem().unwrap().to_str().unwrap().to_string();
-                // Initialize node in map if not exists
-                nodes_map.entry(file_stem.clone()).or_insert(0);
-
-                if let Ok(content) = fs::read_to_string(&path) {
-                    let extracted = extract_links(&content);
-                    for target in extracted {
-                        links.insert((file_stem.clone(), target));
-                    }
-                }
+/// Re-reads changed, added and removed files, then reassembles the graph.
+#[tauri::command]
+pub async fn rebuild_graph(
+    app: AppHandle,
+    vault_state: State<'_, VaultState>,
+    graph_state: State<'_, GraphState>
+) -> Result<GraphData, String> {
+    let vault_path = vault_state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let (data, _store) = refresh_index(&app, &vault_path, &graph_state).await?;
+    Ok(data)
+}
+
+/// The `n` most central notes by `metric`, from the cached graph.
+#[tauri::command]
+pub async fn top_notes(
+    app: AppHandle,
+    vault_state: State<'_, VaultState>,
+    graph_state: State<'_, GraphState>,
+    metric: Metric,
+    n: usize
+) -> Result<Vec<GraphNode>, String> {
+    let data = get_graph(app, vault_state, graph_state).await?;
+    Ok(rank_notes(data.nodes, metric, n))
+}
+
+/// Notes linking to the note `id`.
+#[tauri::command]
+pub async fn get_backlinks(
+    app: AppHandle,
+    vault_state: State<'_, VaultState>,
+    graph_state: State<'_, GraphState>,
+    id: String
+) -> Result<Vec<IndexedNote>, String> {
+    let vault_path = vault_state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let (_, store) = refresh_index(&app, &vault_path, &graph_state).await?;
+    store.as_ref().ok_or("Graph index unavailable")?.backlinks(&id).await
+}
+
+#[tauri::command]
+pub async fn notes_with_tag(
+    app: AppHandle,
+    vault_state: State<'_, VaultState>,
+    graph_state: State<'_, GraphState>,
+    tag: String
+) -> Result<Vec<IndexedNote>, String> {
+    let vault_path = vault_state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let (_, store) = refresh_index(&app, &vault_path, &graph_state).await?;
+    store.as_ref().ok_or("Graph index unavailable")?.notes_with_tag(&tag).await
+}
+
+/// Reconciles the cache and its database with the vault and rebuilds the graph.
+/// The store lock is held by the caller until its query is done, so rebuilds never interleave.
+async fn refresh_index<'a>(
+    app: &AppHandle,
+    vault_path: &Path,
+    graph_state: &'a GraphState
+) -> Result<(GraphData, tokio::sync::MutexGuard<'a, Option<GraphStore>>), String> {
+    let store = sync_index(app, vault_path, graph_state).await?;
+    let data = assemble_graph(&*graph_state.cache.lock().map_err(|e| e.to_string())?);
+
+    // Update Cache
+    *graph_state.last_data.lock().map_err(|e| e.to_string())? = Some((vault_path.to_path_buf(), data.clone()));
+    Ok((data, store))
+}
+
+/// Brings the cache and its database in line with the vault. After a restart
+/// the cache starts from the database, so only files changed while the app was
+/// closed are parsed. Returns the store, locked.
+pub async fn sync_index<'a>(
+    app: &AppHandle,
+    vault_path: &Path,
+    graph_state: &'a GraphState
+) -> Result<tokio::sync::MutexGuard<'a, Option<GraphStore>>, String> {
+    let mut store = graph_state.store.lock().await;
+    if store.as_ref().is_none_or(|store| store.vault_path != vault_path) {
+        *store = None;
+        match open_store(app, vault_path).await {
+            Ok((opened, files)) => {
+                *graph_state.cache.lock().map_err(|e| e.to_string())? = GraphCache { vault_path: vault_path.to_path_buf(), files };
+                *store = Some(opened);
             },
-            Err(e) => println!("Error reading file: {:?}", e),
+            // The database only saves work; the graph is still built from the files
+            Err(e) => println!("Graph index unavailable: {}", e),
         }
     }
-    println!("Found {} files in vault.", file_count);
 
-    // 2. Second Pass: Calculate Weights (Degree Centrality)
+    // Walking and hashing the vault blocks, so it runs off the async runtime. The
+    // cache is lent to that thread; the store lock keeps other refreshes out meanwhile
+    let lent = std::mem::take(&mut *graph_state.cache.lock().map_err(|e| e.to_string())?);
+    let root = vault_path.to_path_buf();
+    let (refreshed, stats) = tokio::task::spawn_blocking(move || {
+        let mut cache = lent;
+        let stats = refresh_cache(&root, &mut cache);
+        (cache, stats)
+    }).await.map_err(|e| e.to_string())?;
+
+    let (updated, removed) = {
+        let mut cache = graph_state.cache.lock().map_err(|e| e.to_string())?;
+        *cache = refreshed;
+        let stats = stats?;
+        if stats.parsed > 0 || !stats.removed.is_empty() {
+            println!("Graph index: {} parsed, {} unchanged, {} removed", stats.parsed, stats.reused, stats.removed.len());
+        }
+        let updated: Vec<(String, FileEntry)> = stats.updated.iter()
+            .filter_map(|relative| cache.files.get(relative).map(|entry| (relative.clone(), entry.clone())))
+            .collect();
+        (updated, stats.removed)
+    };
+    if !updated.is_empty() || !removed.is_empty() {
+        *graph_state.last_data.lock().map_err(|e| e.to_string())? = None;
+    }
+    if let Some(opened) = store.as_ref() {
+        if let Err(e) = opened.save(&updated, &removed).await {
+            println!("Failed to save graph index: {}", e);
+        }
+    }
+    Ok(store)
+}
+
+async fn open_store(app: &AppHandle, vault_path: &Path) -> Result<(GraphStore, HashMap<String, FileEntry>), String> {
+    let store = GraphStore::open(&graph_db_file(app, vault_path)?, vault_path).await?;
+    let files = store.load().await?;
+    Ok((store, files))
+}
+
+/// Brings `cache` in line with the files on disk, parsing only those whose fingerprint changed.
+pub fn refresh_cache(vault_path: &Path, cache: &mut GraphCache) -> Result<RebuildStats, String> {
+    if cache.vault_path != vault_path {
+        *cache = GraphCache { vault_path: vault_path.to_path_buf(), files: HashMap::new() };
+    }
+
+    let mut stats = RebuildStats::default();
+    let mut seen: HashSet<String> = HashSet::new();
+    for path in list_markdown_files(vault_path)? {
+        let relative = path.relative().to_string_lossy().to_string();
+        let Ok(stat) = fs::metadata(&path) else { continue };
+        let (modified, size) = (stat.modified().ok(), stat.len());
+
+        if let Some(entry) = cache.files.get(&relative) {
+            if entry.fingerprint.modified == modified && entry.fingerprint.size == size {
+                seen.insert(relative);
+                stats.reused += 1;
+                continue;
+            }
+        }
+
+        let Ok(content) = fs::read_to_string(&path) else { continue };
+        let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+        seen.insert(relative.clone());
+        stats.updated.push(relative.clone());
+        if let Some(entry) = cache.files.get_mut(&relative) {
+            if entry.fingerprint.hash == hash {
+                entry.fingerprint = Fingerprint { modified, size, hash };
+                stats.reused += 1;
+                continue;
+            }
+        }
+
+        let stem = path.as_path().file_stem().unwrap().to_str().unwrap().to_string();
+        let document = Document::parse(&content).ok();
+        let aliases = document.as_ref().map(frontmatter_aliases).unwrap_or_default();
+        let (id, title, tags, created_at, updated_at) = match document.and_then(|document| document.metadata().ok()) {
+            Some(metadata) => {
+                let updated_at = Some(metadata.updated_at).filter(|u| !u.is_empty());
+                (metadata.id, Some(metadata.title), metadata.tags, Some(metadata.created_at), updated_at)
+            },
+            None => (stem.clone(), None, Vec::new(), None, None),
+        };
+        let fingerprint = Fingerprint { modified, size, hash };
+        let links = parse_note_links(&content);
+        let word_count = split_frontmatter(&content).map(|(_, body)| body).unwrap_or(&content).split_whitespace().count();
+        let entry = FileEntry { fingerprint, stem, id, title, aliases, links, tags, created_at, updated_at, word_count };
+        cache.files.insert(relative, entry);
+        stats.parsed += 1;
+    }
+
+    stats.removed = cache.files.keys().filter(|relative| !seen.contains(*relative)).cloned().collect();
+    stats.removed.sort();
+    for relative in &stats.removed {
+        cache.files.remove(relative);
+    }
+    Ok(stats)
+}
+
+/// Builds nodes and links from cached entries; no file is read.
+pub fn assemble_graph(cache: &GraphCache) -> GraphData {
+    let mut nodes_map: HashMap<String, usize> = HashMap::new(); // ID -> Weight
+    let mut links: HashSet<(String, String, LinkKind)> = HashSet::new();
+    let mut notes: HashMap<String, (String, String)> = HashMap::new(); // ID -> (Label, Path)
+    let mut attachments: HashSet<String> = HashSet::new();
+    // Nodes are keyed by the note's UUID so they survive renames; links may name
+    // the file, its path, its title or an alias. Notes without frontmatter fall back to their stem.
+    let mut resolver = LinkResolver::default();
+    // Unresolved names, so `[[Idea]]` and `[[idea]]` share one ghost
+    let mut ghosts: HashMap<String, String> = HashMap::new(); // Normalized name -> ID
+
+    // 1. First Pass: Collect all Nodes
+    let mut entries: Vec<(&String, &FileEntry)> = cache.files.iter().collect();
+    entries.sort_by_key(|(relative, _)| *relative); // Same winner for clashing names on every build
+    for (relative, entry) in &entries {
+        let label = entry.title.clone().unwrap_or_else(|| entry.stem.clone());
+        // Initialize node in map if not exists
+        nodes_map.entry(entry.id.clone()).or_insert(0);
+        notes.insert(entry.id.clone(), (label, relative.to_string()));
+        resolver.add_note(relative, &entry.id, entry.title.as_deref(), &entry.aliases);
+    }
+
+    // 2. Second Pass: Resolve Links
+    for (relative, entry) in &entries {
+        for note_link in &entry.links {
+            let link = note_link.resolve_from(relative);
+            if link.target.is_empty() {
+                continue; // `[[#Heading]]` stays inside the note
+            }
+            // A note wins over the attachment heuristic, so `[[v1.2 Release]]` finds its note
+            let note = resolver.resolve(&link.target).map(str::to_string);
+            let kind = match note_link.syntax {
+                _ if note.is_none() && is_attachment_target(&link.target) => LinkKind::Attachment,
+                _ if note_link.embed => LinkKind::Embed,
+                LinkSyntax::Markdown => LinkKind::Markdown,
+                LinkSyntax::Wiki => LinkKind::Link,
+            };
+            let target = if let Some(id) = note {
+                id
+            } else if kind == LinkKind::Attachment {
+                // By file name, so `![[scan.pdf]]` and `![](_attachments/scan.pdf)` meet
+                let name = Path::new(&link.target).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(link.target);
+                attachments.insert(name.clone());
+                name
+            } else {
+                ghosts.entry(normalize_name(&link.target)).or_insert(link.target).clone()
+            };
+            links.insert((entry.id.clone(), target, kind));
+        }
+    }
+
+    // 3. Third Pass: Calculate Weights (Degree Centrality)
     // We only count connections where both nodes exist in our vault for strict graph, 
     // BUT for a "Second Brain", a link to a non-existent note is a "Ghost Node".
     // We will include Ghost Nodes with weight 1.
     
     let mut final_links: Vec<GraphLink> = Vec::new();
 
-    for (source, target) in links {
+    for (source, target, kind) in links {
+
         // Increment weight for Source
         *nodes_map.entry(source.clone()).or_insert(0) += 1;
         
         // Increment weight for Target (even if it doesn't exist as a file yet)
         *nodes_map.entry(target.clone()).or_insert(0) += 1;
 
-        final_links.push(GraphLink { source, target });
+        final_links.push(GraphLink { source, target, kind });
     }
 
     let final_nodes: Vec<GraphNode> = nodes_map.into_iter().map(|(id, weight)| {
-        GraphNode {
-            label: id.clone(), // In V0 ID is the Label
-            id,
-            weight
+        match notes.get(&id) {
+            Some((label, path)) => GraphNode {
+                label: label.clone(),
+                path: Some(path.clone()),
+                id,
+                weight,
+                kind: NodeKind::Note,
+                metrics: NodeMetrics::default(),
+            },
+            None => GraphNode {
+                label: id.clone(), // Ghosts and attachments are known by name only
+                path: None,
+                kind: if attachments.contains(&id) { NodeKind::Attachment } else { NodeKind::Ghost },
+                id,
+                weight,
+                metrics: NodeMetrics::default(),
+            },
         }
     }).collect();
 
-    let data = GraphData {
+    let mut data = GraphData {
         nodes: final_nodes,
         links: final_links
     };
-
-    // Update Cache
-    *graph_state.last_data.lock().map_err(|e| e.to_string())? = Some(GraphData {
-        nodes: data.nodes.clone(),
-        links: data.links.clone()
-    });
-
-    Ok(data)
+    compute_metrics(&mut data);
+    data
 }
 
 /// Pure function for RUST-03 Test
+/// Raw targets of every wikilink, embed and relative markdown link outside of code.
 pub fn extract_links(content: &str) -> Vec<String> {
-    let re = Regex::new(r"\[\[(.*?)\]\]").unwrap();
-    re.captures_iter(content)
-        .filter_map(|cap| cap.get(1).map(|m| m.as_str().to_string()))
-        .collect()
+    parse_note_links(content).into_iter().map(|link| link.raw).collect()
+}
+
+/// The file or note a raw `[[...]]` link points at, without `#Heading` or `|alias`.
+pub fn link_target(raw: &str) -> &str {
+    // `\|` is how a pipe is written inside a table cell
+    raw.split(['|', '#']).next().unwrap_or_default().trim().trim_end_matches('\\').trim_end()
+}
+
+/// Whether a link names a file rather than a note (`![[scan.pdf]]`, `[[voice.m4a]]`).
+/// Only a short extension with a letter counts, so `[[Dr. Smith]]` and
+/// `[[Meeting 2024.05.01]]` stay note names; callers try notes first anyway.
+pub fn is_attachment_target(raw: &str) -> bool {
+    match Path::new(link_target(raw)).extension().and_then(|e| e.to_str()) {
+        Some(extension) => {
+            extension.len() <= 5
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+                && extension.chars().any(|c| c.is_ascii_alphabetic())
+                && !extension.eq_ignore_ascii_case("md")
+        },
+        None => false,
+    }
+}
+
+/// Points every `[[target]]` naming one of `old_names` at `new_name`, keeping
+/// `#Heading` anchors, `|alias` display text and the `\|` written in tables.
+/// Links in code are left alone. Returns the new content and the number of links rewritten.
+pub fn rewrite_wikilinks(content: &str, old_names: &[String], new_name: &str) -> (String, usize) {
+    let old_names: Vec<String> = old_names.iter().map(|name| normalize_name(name)).collect();
+    let mut rewritten = String::with_capacity(content.len());
+    let mut copied = 0;
+    let mut count = 0;
+    for (span, link) in note_link_spans(content) {
+        let Some(span) = span.filter(|_| link.syntax == LinkSyntax::Wiki) else { continue };
+        let inner = &content[span.clone()];
+        let name_end = inner.find(['|', '#']).unwrap_or(inner.len());
+        let name = &inner[..name_end];
+        if !old_names.contains(&normalize_name(name.trim_end_matches('\\'))) {
+            continue;
+        }
+        let escape = if name.ends_with('\\') { "\\" } else { "" };
+        rewritten.push_str(&content[copied..span.start]);
+        rewritten.push_str(new_name);
+        rewritten.push_str(escape);
+        rewritten.push_str(&inner[name_end..]);
+        copied = span.end;
+        count += 1;
+    }
+    rewritten.push_str(&content[copied..]);
+    (rewritten, count)
+}
+
+/// Points relative markdown links at the new location of every note in `moves`
+/// (vault-relative `(old, new)` paths). When the note itself moved from
+/// `old_source` to another folder at `new_source`, its other links are rebased
+/// so they reach the same files. `#anchors` are kept. Returns the new content
+/// and the number of links rewritten.
+pub fn rewrite_markdown_links(content: &str, old_source: &str, new_source: &str, moves: &[(String, String)]) -> (String, usize) {
+    let moves: HashMap<String, &str> = moves.iter().map(|(old, new)| (normalize_name(old), new.as_str())).collect();
+    let folder = Path::new(new_source).parent().unwrap_or(Path::new(""));
+    let rebase = Path::new(old_source).parent().unwrap_or(Path::new("")) != folder;
+    let mut rewritten = String::with_capacity(content.len());
+    let mut copied = 0;
+    let mut count = 0;
+    for (span, link) in note_link_spans(content) {
+        let Some(span) = span.filter(|_| link.syntax == LinkSyntax::Markdown) else { continue };
+        let target = link.resolve_from(old_source).target;
+        let moved = moves.get(&normalize_name(&target)).copied();
+        if target.is_empty() || (moved.is_none() && !rebase) {
+            continue;
+        }
+        let target = match moved {
+            // `[x](Old%20Note)` stays without an extension
+            Some(new_target) if !target.to_lowercase().ends_with(".md") => new_target.strip_suffix(".md").unwrap_or(new_target),
+            Some(new_target) => new_target,
+            None => target.as_str(),
+        };
+        let written = &content[span.clone()];
+        let anchor = written.find('#').map(|at| &written[at..]).unwrap_or_default();
+        let destination = format!("{}{}", relative_link(folder, target), anchor);
+        if destination == written {
+            continue;
+        }
+        rewritten.push_str(&content[copied..span.start]);
+        rewritten.push_str(&destination);
+        copied = span.end;
+        count += 1;
+    }
+    rewritten.push_str(&content[copied..]);
+    (rewritten, count)
+}
+
+/// Percent-encoded path from `folder` to the vault-relative `target`, e.g. `../Other%20Note.md`.
+fn relative_link(folder: &Path, target: &str) -> String {
+    let from: Vec<String> = folder.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    let to: Vec<&str> = target.split('/').filter(|part| !part.is_empty()).collect();
+    let shared = from.iter().zip(&to).take_while(|(a, b)| a == *b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - shared];
+    parts.extend(to[shared..].iter().map(|part| urlencoding::encode(part).into_owned()));
+    parts.join("/")
 }
@@ -0,0 +1,179 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::Direction;
+use crate::graph_engine::{GraphData, GraphNode, NodeKind};
+
+const DAMPING: f64 = 0.85;
+const PAGE_RANK_ITERATIONS: usize = 100;
+const PAGE_RANK_TOLERANCE: f64 = 1e-9;
+/// Exact betweenness is O(nodes * links); larger graphs estimate it from this many sources.
+const BETWEENNESS_SAMPLES: usize = 500;
+
+/// Centrality of one node in the directed link graph. Parallel links and
+/// self-links count once and not at all, respectively.
+#[derive(Serialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct NodeMetrics {
+    pub in_degree: usize, // Distinct nodes linking here
+    pub out_degree: usize, // Distinct nodes linked from here
+    pub page_rank: f64, // Sums to 1 over the graph
+    pub betweenness: f64, // Share of shortest paths passing through, 0..1
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    InDegree,
+    OutDegree,
+    PageRank,
+    Betweenness,
+}
+
+impl NodeMetrics {
+    pub fn get(&self, metric: Metric) -> f64 {
+        match metric {
+            Metric::InDegree => self.in_degree as f64,
+            Metric::OutDegree => self.out_degree as f64,
+            Metric::PageRank => self.page_rank,
+            Metric::Betweenness => self.betweenness,
+        }
+    }
+}
+
+/// Fills in `metrics` on every node of `data`.
+pub fn compute_metrics(data: &mut GraphData) {
+    // Sorted ids keep node indices, and so the sampled sources, stable between builds
+    let mut ids: Vec<&str> = data.nodes.iter().map(|node| node.id.as_str()).collect();
+    ids.sort_unstable();
+    let mut graph: DiGraph<(), ()> = DiGraph::with_capacity(ids.len(), data.links.len());
+    let index: HashMap<&str, NodeIndex> = ids.iter().map(|id| (*id, graph.add_node(()))).collect();
+
+    let mut edges: HashSet<(NodeIndex, NodeIndex)> = HashSet::new();
+    for link in &data.links {
+        let (Some(&source), Some(&target)) = (index.get(link.source.as_str()), index.get(link.target.as_str())) else { continue };
+        if source != target && edges.insert((source, target)) {
+            graph.add_edge(source, target, ());
+        }
+    }
+
+    let page_rank = page_rank(&graph);
+    let betweenness = betweenness(&graph);
+    let metrics: HashMap<String, NodeMetrics> = index.iter().map(|(id, &node)| {
+        let metrics = NodeMetrics {
+            in_degree: graph.neighbors_directed(node, Direction::Incoming).count(),
+            out_degree: graph.neighbors_directed(node, Direction::Outgoing).count(),
+            page_rank: page_rank[node.index()],
+            betweenness: betweenness[node.index()],
+        };
+        (id.to_string(), metrics)
+    }).collect();
+
+    for node in &mut data.nodes {
+        node.metrics = metrics.get(&node.id).copied().unwrap_or_default();
+    }
+}
+
+/// The `n` notes scoring highest on `metric`. Ghosts and attachments are left out.
+pub fn rank_notes(nodes: Vec<GraphNode>, metric: Metric, n: usize) -> Vec<GraphNode> {
+    let mut notes: Vec<GraphNode> = nodes.into_iter().filter(|node| node.kind == NodeKind::Note).collect();
+    notes.sort_by(|a, b| {
+        b.metrics.get(metric).total_cmp(&a.metrics.get(metric)).then_with(|| a.label.cmp(&b.label))
+    });
+    notes.truncate(n);
+    notes
+}
+
+/// Power iteration. `petgraph::algo::page_rank` scans every node pair per
+/// iteration, which is too slow for a large vault; this is linear in the links.
+/// Rank of notes without outgoing links is spread evenly over the graph.
+fn page_rank(graph: &DiGraph<(), ()>) -> Vec<f64> {
+    let count = graph.node_count();
+    if count == 0 {
+        return Vec::new();
+    }
+    let share = 1.0 / count as f64;
+    let out_degree: Vec<usize> = graph.node_indices().map(|node| graph.neighbors(node).count()).collect();
+    let mut ranks = vec![share; count];
+
+    for _ in 0..PAGE_RANK_ITERATIONS {
+        let dangling: f64 = graph.node_indices().filter(|node| out_degree[node.index()] == 0).map(|node| ranks[node.index()]).sum();
+        let base = (1.0 - DAMPING) * share + DAMPING * dangling * share;
+        let mut next = vec![base; count];
+        for edge in graph.raw_edges() {
+            let source = edge.source().index();
+            next[edge.target().index()] += DAMPING * ranks[source] / out_degree[source] as f64;
+        }
+
+        let change: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+        ranks = next;
+        if change < PAGE_RANK_TOLERANCE {
+            break;
+        }
+    }
+    ranks
+}
+
+/// Brandes' algorithm on the unweighted directed graph, normalized by the
+/// number of ordered pairs a node could sit between.
+fn betweenness(graph: &DiGraph<(), ()>) -> Vec<f64> {
+    let count = graph.node_count();
+    let mut centrality = vec![0.0; count];
+    if count < 3 {
+        return centrality;
+    }
+
+    // Evenly spread sources; every node when the graph is small enough
+    let step = count.div_ceil(BETWEENNESS_SAMPLES).max(1);
+    let sources: Vec<NodeIndex> = (0..count).step_by(step).map(NodeIndex::new).collect();
+
+    let mut stack: Vec<NodeIndex> = Vec::with_capacity(count);
+    let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+    let mut predecessors: Vec<Vec<NodeIndex>> = vec![Vec::new(); count];
+    let mut paths = vec![0.0_f64; count]; // Number of shortest paths from the source
+    let mut distance: Vec<Option<usize>> = vec![None; count];
+    let mut dependency = vec![0.0_f64; count];
+
+    for &source in &sources {
+        stack.clear();
+        predecessors.iter_mut().for_each(Vec::clear);
+        paths.fill(0.0);
+        distance.fill(None);
+        dependency.fill(0.0);
+        paths[source.index()] = 1.0;
+        distance[source.index()] = Some(0);
+        queue.push_back(source);
+
+        while let Some(node) = queue.pop_front() {
+            stack.push(node);
+            let next_distance = distance[node.index()].unwrap_or_default() + 1;
+            for neighbor in graph.neighbors(node) {
+                let n = neighbor.index();
+                if distance[n].is_none() {
+                    distance[n] = Some(next_distance);
+                    queue.push_back(neighbor);
+                }
+                if distance[n] == Some(next_distance) {
+                    paths[n] += paths[node.index()];
+                    predecessors[n].push(node);
+                }
+            }
+        }
+
+        while let Some(node) = stack.pop() {
+            let n = node.index();
+            for &predecessor in &predecessors[n] {
+                let p = predecessor.index();
+                dependency[p] += paths[p] / paths[n] * (1.0 + dependency[n]);
+            }
+            if node != source {
+                centrality[n] += dependency[n];
+            }
+        }
+    }
+
+    let scale = (count as f64 / sources.len() as f64) / ((count - 1) * (count - 2)) as f64;
+    for value in &mut centrality {
+        *value *= scale;
+    }
+    centrality
+}
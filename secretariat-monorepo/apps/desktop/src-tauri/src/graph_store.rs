@@ -0,0 +1,231 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+use tauri::{AppHandle, Manager};
+use crate::graph_engine::{FileEntry, Fingerprint};
+use crate::vault_manager::normalize_tag;
+use crate::wikilink::{normalize_name, LinkSyntax, NoteLink};
+
+/// One database per vault, under the app data dir so it never syncs.
+const GRAPH_DB_DIR: &str = "graph";
+
+/// Schema changes go in `migrations/` as new numbered files; applied ones are never edited.
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct IndexedNote {
+    pub id: String,
+    pub title: String,
+    pub path: String,
+}
+
+/// SQLite copy of the graph cache. It is only a cache: the vault is the source
+/// of truth and the database is reconciled against it before every use.
+pub struct GraphStore {
+    pub vault_path: PathBuf,
+    pool: SqlitePool,
+}
+
+impl GraphStore {
+    /// Opens (or creates) the database at `db_path` and applies pending migrations.
+    pub async fn open(db_path: &Path, vault_path: &Path) -> Result<Self, String> {
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true)
+            .foreign_keys(true);
+        // A single connection keeps writes ordered; the app is the only client
+        let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await.map_err(|e| e.to_string())?;
+        MIGRATOR.run(&pool).await.map_err(|e| e.to_string())?;
+        Ok(Self { vault_path: vault_path.to_path_buf(), pool })
+    }
+
+    /// Every stored entry, keyed by vault-relative path.
+    pub async fn load(&self) -> Result<HashMap<String, FileEntry>, String> {
+        let mut entries: HashMap<String, FileEntry> = HashMap::new();
+        let rows = sqlx::query("SELECT path, modified_ns, size, hash, stem, note_id, title, created_at, updated_at, word_count FROM files")
+            .fetch_all(&self.pool).await.map_err(|e| e.to_string())?;
+        for row in rows {
+            let modified: Option<i64> = row.get("modified_ns");
+            let fingerprint = Fingerprint {
+                modified: modified.map(|ns| UNIX_EPOCH + Duration::from_nanos(ns as u64)),
+                size: row.get::<i64, _>("size") as u64,
+                hash: row.get("hash"),
+            };
+            let entry = FileEntry {
+                fingerprint,
+                stem: row.get("stem"),
+                id: row.get("note_id"),
+                title: row.get("title"),
+                aliases: Vec::new(),
+                links: Vec::new(),
+                tags: Vec::new(),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+                word_count: row.get::<i64, _>("word_count") as usize,
+            };
+            entries.insert(row.get("path"), entry);
+        }
+
+        let rows = sqlx::query("SELECT source_path, raw, syntax, embed FROM links ORDER BY source_path, position")
+            .fetch_all(&self.pool).await.map_err(|e| e.to_string())?;
+        for row in rows {
+            if let Some(entry) = entries.get_mut(row.get::<&str, _>("source_path")) {
+                let syntax = match row.get::<&str, _>("syntax") {
+                    "markdown" => LinkSyntax::Markdown,
+                    _ => LinkSyntax::Wiki,
+                };
+                entry.links.push(NoteLink { raw: row.get("raw"), syntax, embed: row.get("embed") });
+            }
+        }
+        let rows = sqlx::query("SELECT path, alias FROM aliases ORDER BY path, position")
+            .fetch_all(&self.pool).await.map_err(|e| e.to_string())?;
+        for row in rows {
+            if let Some(entry) = entries.get_mut(row.get::<&str, _>("path")) {
+                entry.aliases.push(row.get("alias"));
+            }
+        }
+        let rows = sqlx::query("SELECT path, tag FROM tags").fetch_all(&self.pool).await.map_err(|e| e.to_string())?;
+        for row in rows {
+            if let Some(entry) = entries.get_mut(row.get::<&str, _>("path")) {
+                entry.tags.push(row.get("tag"));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Writes changed entries and drops removed ones in one transaction.
+    pub async fn save(&self, updated: &[(String, FileEntry)], removed: &[String]) -> Result<(), String> {
+        if updated.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        for path in removed {
+            // Links and tags go with the file
+            sqlx::query("DELETE FROM files WHERE path = ?1").bind(path).execute(&mut *tx).await.map_err(|e| e.to_string())?;
+        }
+        for (path, entry) in updated {
+            sqlx::query("DELETE FROM files WHERE path = ?1").bind(path).execute(&mut *tx).await.map_err(|e| e.to_string())?;
+            let modified = entry.fingerprint.modified
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos() as i64);
+            sqlx::query(
+                "INSERT INTO files (path, modified_ns, size, hash, stem, note_id, title, stem_key, title_key, path_key, created_at, updated_at, word_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"
+            )
+                .bind(path)
+                .bind(modified)
+                .bind(entry.fingerprint.size as i64)
+                .bind(&entry.fingerprint.hash)
+                .bind(&entry.stem)
+                .bind(&entry.id)
+                .bind(&entry.title)
+                .bind(normalize_name(&entry.stem))
+                .bind(entry.title.as_deref().map(normalize_name))
+                .bind(normalize_name(&path.replace('\\', "/")))
+                .bind(&entry.created_at)
+                .bind(&entry.updated_at)
+                .bind(entry.word_count as i64)
+                .execute(&mut *tx).await.map_err(|e| e.to_string())?;
+
+            for (position, link) in entry.links.iter().enumerate() {
+                let syntax = match link.syntax {
+                    LinkSyntax::Wiki => "wiki",
+                    LinkSyntax::Markdown => "markdown",
+                };
+                sqlx::query(
+                    "INSERT INTO links (source_path, position, raw, target_key, syntax, embed) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+                )
+                    .bind(path)
+                    .bind(position as i64)
+                    .bind(&link.raw)
+                    .bind(normalize_name(&link.resolve_from(path).target))
+                    .bind(syntax)
+                    .bind(link.embed)
+                    .execute(&mut *tx).await.map_err(|e| e.to_string())?;
+            }
+            for (position, alias) in entry.aliases.iter().enumerate() {
+                sqlx::query("INSERT INTO aliases (path, position, alias, alias_key) VALUES (?1, ?2, ?3, ?4)")
+                    .bind(path)
+                    .bind(position as i64)
+                    .bind(alias)
+                    .bind(normalize_name(alias))
+                    .execute(&mut *tx).await.map_err(|e| e.to_string())?;
+            }
+            for tag in &entry.tags {
+                sqlx::query("INSERT OR IGNORE INTO tags (path, tag, tag_key) VALUES (?1, ?2, ?3)")
+                    .bind(path)
+                    .bind(tag)
+                    .bind(normalize_tag(tag))
+                    .execute(&mut *tx).await.map_err(|e| e.to_string())?;
+            }
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())
+    }
+
+    /// Notes linking to the note `id`, by its path, file name, title or an alias.
+    /// A name two notes share counts only for the one `LinkResolver` picks: the
+    /// best-ranked kind of name, then the first path.
+    pub async fn backlinks(&self, id: &str) -> Result<Vec<IndexedNote>, String> {
+        let rows = sqlx::query(
+            "WITH names (path, note_id, name_key, rank) AS (
+                 SELECT path, note_id, path_key, 0 FROM files
+                 UNION ALL SELECT path, note_id, stem_key, 1 FROM files
+                 UNION ALL SELECT path, note_id, title_key, 2 FROM files WHERE title_key IS NOT NULL
+                 UNION ALL SELECT aliases.path, files.note_id, alias_key, 3 FROM aliases JOIN files USING (path)
+             ),
+             ranked AS (
+                 SELECT path, note_id, name_key, ROW_NUMBER() OVER (PARTITION BY name_key ORDER BY rank, path) AS place
+                 FROM names
+                 WHERE name_key IN (SELECT name_key FROM names WHERE note_id = ?1)
+             )
+             SELECT DISTINCT source.note_id, COALESCE(source.title, source.stem) AS title, source.path
+             FROM ranked
+             JOIN links ON links.target_key = ranked.name_key
+             JOIN files source ON source.path = links.source_path
+             WHERE ranked.place = 1 AND ranked.note_id = ?1 AND source.path != ranked.path
+             ORDER BY source.path"
+        )
+            .bind(id)
+            .fetch_all(&self.pool).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(indexed_note).collect())
+    }
+
+    /// Notes carrying `tag`, compared like `list_notes` does: without case or a leading `#`.
+    pub async fn notes_with_tag(&self, tag: &str) -> Result<Vec<IndexedNote>, String> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT files.note_id, COALESCE(files.title, files.stem) AS title, files.path
+             FROM tags JOIN files ON files.path = tags.path
+             WHERE tags.tag_key = ?1
+             ORDER BY files.path"
+        )
+            .bind(normalize_tag(tag))
+            .fetch_all(&self.pool).await.map_err(|e| e.to_string())?;
+        Ok(rows.iter().map(indexed_note).collect())
+    }
+}
+
+fn indexed_note(row: &sqlx::sqlite::SqliteRow) -> IndexedNote {
+    IndexedNote {
+        id: row.get("note_id"),
+        title: row.get("title"),
+        path: row.get("path"),
+    }
+}
+
+/// Database file of `vault_path`, named after a hash of its location.
+pub fn graph_db_file(app: &AppHandle, vault_path: &Path) -> Result<PathBuf, String> {
+    let hash = format!("{:x}", Sha256::digest(vault_path.to_string_lossy().as_bytes()));
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join(GRAPH_DB_DIR);
+    Ok(dir.join(format!("{}.db", &hash[..16])))
+}
+
@@ -0,0 +1,175 @@
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use chrono::{NaiveDateTime, Utc};
+use similar::{ChangeTag, TextDiff};
+use crate::frontmatter::Document;
+use crate::vault_config::load_config;
+use crate::vault_manager::{
+    parse_note, save_file, save_note, touch_metadata, Note, VaultPath, VaultState,
+};
+
+/// Snapshots of previous note contents, one folder per note id.
+const HISTORY_DIR: &str = ".secretariat/history";
+const REVISION_TIMESTAMP: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Revision {
+    pub revision_id: String,
+    pub version: u64,  // Version of the note when it was replaced
+    pub saved_at: String, // ISO 8601
+    pub size: u64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffMode {
+    #[default]
+    Line,
+    Word,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DiffChunk {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Revisions of a note, newest first.
+#[tauri::command]
+pub fn list_revisions(state: State<'_, VaultState>, note_id: String) -> Result<Vec<Revision>, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    load_revisions(&history_dir(&vault_path, &note_id)?)
+}
+
+/// Compares two revisions of a note. Without `to_revision` the current file is used.
+#[tauri::command]
+pub fn diff_revisions(
+    state: State<'_, VaultState>,
+    note_id: String,
+    from_revision: String,
+    to_revision: Option<String>,
+    mode: Option<DiffMode>
+) -> Result<Vec<DiffChunk>, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+
+    let old = read_revision(&vault_path, &note_id, &from_revision)?;
+    let new = match to_revision {
+        Some(revision_id) => read_revision(&vault_path, &note_id, &revision_id)?,
+        None => fs::read_to_string(state.find_note(&note_id)?.1).map_err(|e| e.to_string())?,
+    };
+
+    Ok(diff_text(&old, &new, mode.unwrap_or_default()))
+}
+
+/// Brings back an old revision as a new version of the note.
+/// The content being replaced is snapshotted like any other write, so this is reversible too.
+#[tauri::command]
+pub fn restore_revision(state: State<'_, VaultState>, note_id: String, revision_id: String) -> Result<Note, String> {
+    let (vault_path, note_path) = state.find_note(&note_id)?;
+
+    let (current, _) = parse_note(&fs::read_to_string(&note_path).map_err(|e| e.to_string())?)?;
+    let mut document = Document::parse(&read_revision(&vault_path, &note_id, &revision_id)?)?;
+    let mut metadata = document.metadata()?;
+
+    // The filename follows the current title; versions only move forward
+    metadata.title = current.title;
+    metadata.version = current.version;
+    touch_metadata(&mut metadata);
+    document.set_metadata(&metadata)?;
+    save_note(&vault_path, &note_path, document.render()?)?;
+
+    Ok(Note::new(metadata, document.content().to_string()))
+}
+
+/// Called by `save_note` before a note is overwritten: keeps the previous content
+/// under `.secretariat/history/<id>/` and enforces the per-note retention limit.
+pub fn snapshot_revision(vault_path: &Path, note_path: &VaultPath) -> Result<(), String> {
+    let Ok(previous) = fs::read_to_string(note_path) else { return Ok(()) };
+    // Only managed notes have an id to file history under
+    let Ok((metadata, _)) = parse_note(&previous) else { return Ok(()) };
+
+    let dir = history_dir(vault_path, &metadata.id)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let revision_id = format!("{}-v{}", Utc::now().format(REVISION_TIMESTAMP), metadata.version);
+    save_file(&dir.join(format!("{}.md", revision_id)), previous)?;
+
+    let max_revisions = load_config(vault_path)?.history.max_revisions;
+    for expired in load_revisions(&dir)?.iter().skip(max_revisions) {
+        fs::remove_file(dir.join(format!("{}.md", expired.revision_id))).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn diff_text(old: &str, new: &str, mode: DiffMode) -> Vec<DiffChunk> {
+    let diff = match mode {
+        DiffMode::Line => TextDiff::from_lines(old, new),
+        DiffMode::Word => TextDiff::from_words(old, new),
+    };
+
+    // Merge consecutive changes of the same kind into one chunk
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    for change in diff.iter_all_changes() {
+        let op = match change.tag() {
+            ChangeTag::Equal => DiffOp::Equal,
+            ChangeTag::Insert => DiffOp::Insert,
+            ChangeTag::Delete => DiffOp::Delete,
+        };
+        match chunks.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(change.value()),
+            _ => chunks.push(DiffChunk { op, text: change.value().to_string() }),
+        }
+    }
+    chunks
+}
+
+fn history_dir(vault_path: &Path, note_id: &str) -> Result<PathBuf, String> {
+    // Ids come from frontmatter and the webview: they must stay a single path segment
+    if note_id.is_empty() || note_id.contains(['/', '\\']) || note_id.starts_with('.') {
+        return Err(format!("Invalid note id: {}", note_id));
+    }
+    Ok(vault_path.join(HISTORY_DIR).join(note_id))
+}
+
+fn read_revision(vault_path: &Path, note_id: &str, revision_id: &str) -> Result<String, String> {
+    let revision = load_revisions(&history_dir(vault_path, note_id)?)?
+        .into_iter()
+        .find(|r| r.revision_id == revision_id)
+        .ok_or("Revision not found")?;
+    let path = history_dir(vault_path, note_id)?.join(format!("{}.md", revision.revision_id));
+    fs::read_to_string(path).map_err(|e| e.to_string())
+}
+
+fn load_revisions(dir: &Path) -> Result<Vec<Revision>, String> {
+    let Ok(entries) = fs::read_dir(dir) else { return Ok(Vec::new()) };
+
+    let mut revisions = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(revision_id) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+        let Some((timestamp, version)) = revision_id.rsplit_once("-v") else { continue };
+        let Ok(saved_at) = NaiveDateTime::parse_from_str(timestamp, REVISION_TIMESTAMP) else { continue };
+
+        revisions.push(Revision {
+            revision_id: revision_id.to_string(),
+            version: version.parse().unwrap_or(0),
+            saved_at: saved_at.and_utc().to_rfc3339(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+    }
+    // Timestamps sort lexicographically
+    revisions.sort_by(|a, b| b.revision_id.cmp(&a.revision_id));
+    Ok(revisions)
+}
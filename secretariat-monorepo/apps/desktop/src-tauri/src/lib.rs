@@ -1,41 +1,128 @@
 mod vault_manager;
+mod device_identity;
+mod frontmatter;
 mod ai_orchestrator;
 mod graph_engine;
+mod graph_store;
+mod graph_metrics;
+mod wikilink;
 mod sync_engine;
+mod trash_manager;
+mod vault_config;
+mod template_manager;
+mod history_manager;
+mod attachment_manager;
+mod folder_manager;
+mod filename_policy;
+mod batch_manager;
+mod vault_watcher;
+mod vault_registry;
+mod vault_doctor;
+mod migration_manager;
 mod tests;
 
-use std::sync::Mutex;
 use std::path::PathBuf;
 use tauri::Manager;
+use tauri_plugin_deep_link::DeepLinkExt;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Registered first: a link clicked while the app runs is passed to this
+        // instance and arrives through `on_open_url` below
+        .plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {}))
+        .plugin(tauri_plugin_deep_link::init())
         .setup(|app| {
-            let path_resolver = app.path();
-            let docs_dir = path_resolver.document_dir().unwrap_or(PathBuf::from("."));
-            let vault_path = docs_dir.join("SecretariatVault");
+            // Every write is stamped with the device id, so there is no running without one
+            let device = app.path().app_data_dir().map_err(|e| e.to_string())
+                .and_then(|dir| device_identity::init_device(&dir))
+                .map_err(|e| format!("Device identity unavailable: {}", e))?;
+            println!("Running as device {} ({})", device.name, device.device_id);
+
+            // Reopen the vault used last time; first launch gets the default one
+            let vault_path = match vault_registry::last_vault(app.handle()) {
+                Some(vault_path) => vault_path,
+                None => {
+                    let path_resolver = app.path();
+                    let docs_dir = path_resolver.document_dir().unwrap_or(PathBuf::from("."));
+                    let vault_path = docs_dir.join("SecretariatVault");
+                    
+                    if !vault_path.exists() {
+                        std::fs::create_dir_all(&vault_path)?;
+                    }
+                    vault_path
+                },
+            };
+
+            app.manage(vault_manager::VaultState::new(vault_path.clone()));
             
-            if !vault_path.exists() {
-                std::fs::create_dir_all(&vault_path)?;
+            app.manage(graph_engine::GraphState::new());
+            app.manage(vault_watcher::WatcherState::new());
+
+            // Recovery, watcher and the recent list are handled like any later switch
+            if let Err(e) = vault_registry::activate_vault(app.handle(), &vault_path) {
+                println!("Failed to open vault {}: {}", vault_path.display(), e);
             }
-            
-            app.manage(vault_manager::VaultState {
-                vault_path: Mutex::new(vault_path),
+
+            // `secretariat://note/<uuid>` links; the webview reads the launch link with `getCurrent()`
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            app.deep_link().register_all()?;
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                let urls: Vec<String> = event.urls().iter().map(|url| url.to_string()).collect();
+                vault_manager::route_deep_links(&handle, &urls);
             });
-            
-            app.manage(graph_engine::GraphState::new());
-            
+
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             vault_manager::create_note,
             vault_manager::read_note,
+            vault_manager::get_note_by_id,
+            vault_manager::open_deep_link,
+            vault_manager::update_note,
+            vault_manager::rename_note,
+            vault_manager::list_notes,
+            folder_manager::create_folder,
+            folder_manager::move_note,
+            folder_manager::rename_folder,
+            folder_manager::folder_tree,
+            batch_manager::batch_notes,
+            batch_manager::undo_batch,
+            batch_manager::list_batches,
+            vault_doctor::vault_doctor,
+            vault_doctor::apply_doctor_fix,
+            migration_manager::migrate_vault,
+            vault_manager::open_daily_note,
+            vault_manager::append_to_daily_note,
+            frontmatter::update_frontmatter,
+            history_manager::list_revisions,
+            history_manager::diff_revisions,
+            history_manager::restore_revision,
+            attachment_manager::import_attachment,
+            attachment_manager::cleanup_attachments,
+            template_manager::list_templates,
+            template_manager::create_note_from_template,
+            device_identity::get_device_info,
+            vault_registry::open_vault,
+            vault_registry::create_vault,
+            vault_registry::list_recent_vaults,
+            vault_registry::switch_vault,
+            vault_config::get_vault_config,
+            vault_config::update_vault_config,
+            trash_manager::delete_note,
+            trash_manager::list_trash,
+            trash_manager::restore_note,
+            trash_manager::purge_trash,
             vault_manager::get_app_theme,
             ai_orchestrator::process_input_with_ai,
             ai_orchestrator::chat_with_vault,
             graph_engine::rebuild_graph,
+            graph_engine::get_graph,
+            graph_engine::top_notes,
+            graph_engine::get_backlinks,
+            graph_engine::notes_with_tag,
             sync_engine::sync_vault
         ])
         .run(tauri::generate_context!())
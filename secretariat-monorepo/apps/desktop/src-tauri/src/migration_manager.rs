@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use chrono::{DateTime, Utc};
+use serde_yaml::Value;
+use crate::batch_manager::BatchFailure;
+use crate::device_identity::current_device_id;
+use crate::frontmatter::Document;
+use crate::vault_config::{load_config, save_config};
+use crate::vault_manager::{list_markdown_files, save_note, VaultPath, VaultState};
+
+/// Frontmatter schema this build writes. Bump it and append to `MIGRATIONS`
+/// whenever notes gain a field.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One schema step. Steps only add what is missing, so they are safe to run
+/// again on notes that were already migrated, synced in, or dropped into the vault.
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&mut Document, &VaultPath),
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "Managed note fields", apply: add_managed_fields },
+];
+
+#[derive(Serialize, Debug)]
+pub struct MigratedNote {
+    pub path: String,
+    pub added: Vec<String>, // Frontmatter keys the migration adds
+}
+
+#[derive(Serialize, Debug)]
+pub struct MigrationReport {
+    pub from_version: u32, // Schema the vault was at before this run
+    pub to_version: u32,
+    pub steps: Vec<String>,
+    pub migrated: Vec<MigratedNote>,
+    pub unchanged: usize,
+    pub failed: Vec<BatchFailure>,
+}
+
+/// Brings every note up to the current schema. Plain markdown gets frontmatter;
+/// bodies are never changed. Nothing is written unless `dry_run` is `false`.
+#[tauri::command]
+pub fn migrate_vault(state: State<'_, VaultState>, dry_run: Option<bool>) -> Result<MigrationReport, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    run_migrations(&vault_path, dry_run.unwrap_or(true))
+}
+
+pub fn run_migrations(vault_path: &Path, dry_run: bool) -> Result<MigrationReport, String> {
+    let mut config = load_config(vault_path)?;
+    let mut report = MigrationReport {
+        from_version: config.schema_version,
+        to_version: SCHEMA_VERSION,
+        steps: MIGRATIONS.iter().map(|m| format!("{}: {}", m.version, m.description)).collect(),
+        migrated: Vec::new(),
+        unchanged: 0,
+        failed: Vec::new(),
+    };
+
+    for path in list_markdown_files(vault_path)? {
+        let relative = path.relative().to_string_lossy().to_string();
+        match migrate_note(vault_path, &path, dry_run) {
+            Ok(added) if added.is_empty() => report.unchanged += 1,
+            Ok(added) => report.migrated.push(MigratedNote { path: relative, added }),
+            Err(error) => report.failed.push(BatchFailure { path: relative, error }),
+        }
+    }
+
+    // Failed notes are retried on the next run, whatever the recorded version
+    if !dry_run && report.failed.is_empty() && config.schema_version != SCHEMA_VERSION {
+        config.schema_version = SCHEMA_VERSION;
+        save_config(vault_path, &config)?;
+    }
+    println!("Migration: {} notes updated, {} failed", report.migrated.len(), report.failed.len());
+    Ok(report)
+}
+
+/// Migrates one file, returning the keys that were (or would be) added.
+pub fn migrate_note(vault_path: &Path, path: &VaultPath, dry_run: bool) -> Result<Vec<String>, String> {
+    let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut document = Document::parse(&raw)?;
+    let added = upgrade_document(&mut document, path);
+    if added.is_empty() {
+        return Ok(added);
+    }
+    document.metadata()?; // Existing fields of the wrong type need a human
+    if !dry_run {
+        save_note(vault_path, path, document.render()?)?;
+    }
+    Ok(added)
+}
+
+/// Runs every schema step on `document` and returns the keys it added.
+pub fn upgrade_document(document: &mut Document, path: &VaultPath) -> Vec<String> {
+    let before: Vec<Value> = document.fields().keys().cloned().collect();
+    for migration in MIGRATIONS {
+        (migration.apply)(document, path);
+    }
+    document.fields().keys()
+        .filter(|key| !before.contains(key))
+        .filter_map(|key| key.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Schema 1: what listing, history and sync need. Dates come from the file's mtime.
+fn add_managed_fields(document: &mut Document, path: &VaultPath) {
+    let created_at = match document.get("created_at") {
+        Some(Value::String(created_at)) => created_at.clone(),
+        _ => modified_at(path).unwrap_or_else(|| Utc::now().to_rfc3339()),
+    };
+    let title = path.as_path().file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+
+    set_missing(document, "id", || Value::from(uuid::Uuid::new_v4().to_string()));
+    set_missing(document, "title", || Value::from(title));
+    set_missing(document, "created_at", || Value::from(created_at.clone()));
+    set_missing(document, "updated_at", || Value::from(created_at));
+    set_missing(document, "version", || Value::from(1));
+    set_missing(document, "device_id", || Value::from(current_device_id()));
+}
+
+fn set_missing(document: &mut Document, key: &str, value: impl FnOnce() -> Value) {
+    if document.get(key).is_none() {
+        document.set(key, value());
+    }
+}
+
+fn modified_at(path: &VaultPath) -> Option<String> {
+    let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+    Some(DateTime::<Utc>::from(modified).to_rfc3339())
+}
@@ -105,6 +105,15 @@ impl SupabaseClient {
 
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
+use std::path::Path;
+use chrono::Utc;
+use crate::device_identity::current_device_id;
+use crate::frontmatter::Document;
+use crate::vault_manager::{list_markdown_files, save_json, VaultPath};
+use crate::trash_manager::{load_tombstones, move_to_trash, record_tombstone};
+
+/// When this device last synced the vault, and as whom.
+const SYNC_STATE_FILE: &str = ".secretariat/sync_state.json";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SyncState {
@@ -121,17 +130,35 @@ impl SyncState {
     }
 }
 
+pub fn load_sync_state(vault_path: &Path) -> Result<SyncState, String> {
+    let device_id = current_device_id();
+    match fs::read_to_string(vault_path.join(SYNC_STATE_FILE)) {
+        // The vault folder may have been copied from another machine
+        Ok(json) => serde_json::from_str::<SyncState>(&json)
+            .map(|state| if state.device_id == device_id { state } else { SyncState::new(device_id) })
+            .map_err(|e| e.to_string()),
+        Err(_) => Ok(SyncState::new(device_id)),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NoteSyncMetadata {
     pub id: String,
+    #[serde(default)]
     pub updated_at: String,
+    #[serde(default)]
     pub version: u64,
+    #[serde(default)] // Tombstone: the note was deleted at this version
+    pub deleted: bool,
+    #[serde(default)] // Device that wrote this version
+    pub device_id: String,
 }
 
 #[derive(Debug)]
 pub struct SyncPlan {
     pub to_upload: Vec<String>,   // ID
     pub to_download: Vec<String>, // ID
+    pub to_delete: Vec<String>,   // ID, deleted on another device
     pub conflicts: Vec<String>,   // ID
 }
 
@@ -140,6 +167,7 @@ pub struct SyncEngine;
 impl SyncEngine {
     pub async fn run_sync(vault_path: PathBuf) -> Result<String, String> {
         let client = SupabaseClient::new();
+        let mut sync_state = load_sync_state(&vault_path)?;
         
         let remote_index_res = client.download_file("vault", "index.json").await;
         // Load Remote Index
@@ -154,11 +182,28 @@ impl SyncEngine {
         let plan = Self::calculate_sync_plan(&local_index, &remote_index);
         let mut uploads_count = 0;
 
+        // Conflicting versions are left alone until they can be merged
+        for id in &plan.conflicts {
+            if let (Some(local), Some(remote)) = (local_index.get(id), remote_index.get(id)) {
+                println!(
+                    "Sync conflict on {} at version {}: edited here by {} ({}) and on device {} ({})",
+                    id, local.version, sync_state.device_id, local.updated_at, remote.device_id, remote.updated_at
+                );
+            }
+        }
+
         // 4. Execute Uploads
         for id in plan.to_upload {
+            // Tombstones only travel through the index
+            if let Some(meta) = local_index.get(&id).filter(|meta| meta.deleted) {
+                remote_index.insert(id.clone(), meta.clone());
+                uploads_count += 1;
+                continue;
+            }
+
             if let Some(path) = id_to_path.get(&id) {
                 if let Ok(content) = fs::read(path) {
-                    let filename = path.file_name().unwrap().to_str().unwrap();
+                    let filename = path.as_path().file_name().unwrap().to_str().unwrap();
                     // Upload File
                     client.upload_file("vault", filename, content).await?;
                     
@@ -171,57 +216,68 @@ impl SyncEngine {
             }
         }
         
+        // Apply deletions made on other devices, keeping their tombstone version
+        // so this device does not publish the note again
+        let mut deleted_count = 0;
+        for id in plan.to_delete {
+            if let (Some(path), Some(remote_meta)) = (id_to_path.get(&id), remote_index.get(&id)) {
+                move_to_trash(&vault_path, path)?;
+                record_tombstone(&vault_path, &id, remote_meta.version, &remote_meta.device_id)?;
+                deleted_count += 1;
+            }
+        }
+
         // 5. Save New Index to Remote
         if uploads_count > 0 {
              let index_json = serde_json::to_string(&remote_index).map_err(|e| e.to_string())?;
              client.upload_file("vault", "index.json", index_json.into_bytes()).await?;
         }
 
-        Ok(format!("Sync Complete. Uploaded: {}. Remote Index Size: {}", uploads_count, remote_index.len()))
+        sync_state.last_synced_at = Utc::now().to_rfc3339();
+        save_json(&vault_path.join(SYNC_STATE_FILE), &sync_state)?;
+
+        Ok(format!(
+            "Sync Complete. Uploaded: {}. Deleted: {}. Conflicts: {}. Remote Index Size: {}",
+            uploads_count, deleted_count, plan.conflicts.len(), remote_index.len()
+        ))
     }
     
-    pub fn scan_local_vault(vault_path: &PathBuf) -> Result<(HashMap<String, NoteSyncMetadata>, HashMap<String, PathBuf>), String> {
+    pub fn scan_local_vault(vault_path: &PathBuf) -> Result<(HashMap<String, NoteSyncMetadata>, HashMap<String, VaultPath>), String> {
         let mut index = HashMap::new();
-        let mut paths = HashMap::new();
-        let pattern = vault_path.join("**/*.md");
-        let pattern_str = pattern.to_str().ok_or("Invalid vault path")?;
-
-        for entry in glob::glob(pattern_str).map_err(|e| e.to_string())? {
-            if let Ok(path) = entry {
-                if let Ok(content) = fs::read_to_string(&path) {
-                    if let Some(metadata) = Self::parse_frontmatter(&content) {
-                        index.insert(metadata.id.clone(), metadata.clone());
-                        paths.insert(metadata.id.clone(), path);
-                    }
-                }
+        let mut paths: HashMap<String, VaultPath> = HashMap::new();
+
+        for path in list_markdown_files(vault_path)? {
+            let metadata = fs::read_to_string(&path).ok().and_then(|content| Self::parse_frontmatter(&content));
+            let Some(metadata) = metadata else {
+                println!("Sync skipped {}: unreadable or no id (run the vault doctor)", path.relative().display());
+                continue;
+            };
+            // A copied note must not replace the original in the index
+            if let Some(first) = paths.get(&metadata.id) {
+                println!("Sync skipped {}: id {} is already used by {}", path.relative().display(), metadata.id, first.relative().display());
+                continue;
             }
+            paths.insert(metadata.id.clone(), path);
+            index.insert(metadata.id.clone(), metadata);
+        }
+
+        // Notes deleted here are still part of the index, as tombstones
+        for (id, tombstone) in load_tombstones(vault_path)? {
+            index.entry(id.clone()).or_insert(NoteSyncMetadata {
+                id,
+                updated_at: tombstone.deleted_at,
+                version: tombstone.version,
+                deleted: true,
+                device_id: tombstone.device_id,
+            });
         }
         Ok((index, paths))
     }
 
     fn parse_frontmatter(content: &str) -> Option<NoteSyncMetadata> {
-        if content.starts_with("---") {
-            if let Some(end) = content[3..].find("---") {
-                let yaml_str = &content[3..end+3];
-                 #[derive(Deserialize)]
-                struct MinimalMeta {
-                    id: String,
-                    #[serde(default)] 
-                    updated_at: String,
-                    #[serde(default)] 
-                    version: u64,
-                }
-                
-                if let Ok(meta) = serde_yaml::from_str::<MinimalMeta>(yaml_str) {
-                    return Some(NoteSyncMetadata {
-                        id: meta.id,
-                        updated_at: meta.updated_at,
-                        version: meta.version
-                    });
-                }
-            }
-        }
-        None
+        // Same YAML view as the vault; a note on disk is never a tombstone
+        let meta = Document::parse(content).ok()?.view::<NoteSyncMetadata>().ok()?;
+        Some(NoteSyncMetadata { deleted: false, ..meta })
     }
     
     pub fn calculate_sync_plan(
@@ -231,6 +287,7 @@ impl SyncEngine {
         let mut plan = SyncPlan {
             to_upload: Vec::new(),
             to_download: Vec::new(),
+            to_delete: Vec::new(),
             conflicts: Vec::new(),
         };
 
@@ -243,10 +300,18 @@ impl SyncEngine {
                          // We are ahead. Upload.
                          plan.to_upload.push(id.clone());
                     } else if remote_meta.version > local_meta.version {
-                        // Remote is ahead. Download.
-                        plan.to_download.push(id.clone());
-                    } else {
-                        // Versions equal.
+                        if remote_meta.deleted {
+                            // Deleted elsewhere after our last edit.
+                            if !local_meta.deleted {
+                                plan.to_delete.push(id.clone());
+                            }
+                        } else {
+                            // Remote is ahead. Download.
+                            plan.to_download.push(id.clone());
+                        }
+                    } else if local_meta.deleted != remote_meta.deleted || local_meta.updated_at != remote_meta.updated_at {
+                        // Versions equal, but two devices wrote them independently.
+                        plan.conflicts.push(id.clone());
                     }
                 },
                 None => {
@@ -257,8 +322,8 @@ impl SyncEngine {
         }
 
         // 2. Check Remote files that don't exist Locally
-        for (id, _remote_meta) in remote_files {
-            if !local_files.contains_key(id) {
+        for (id, remote_meta) in remote_files {
+            if !local_files.contains_key(id) && !remote_meta.deleted {
                 plan.to_download.push(id.clone());
             }
         }
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+use crate::device_identity::current_device_name;
+use crate::filename_policy::unique_note_path;
+use crate::frontmatter::split_frontmatter;
+use crate::vault_manager::{
+    new_metadata, render_note, save_note, try_format,
+    NoteMetadata, VaultPath, VaultState,
+};
+
+/// Markdown templates live in a regular vault folder so they can be edited like notes.
+pub const TEMPLATES_DIR: &str = "_templates";
+
+/// Keys the app owns; a template cannot preset them.
+const RESERVED_FIELDS: [&str; 6] = ["id", "title", "created_at", "updated_at", "version", "device_id"];
+
+#[derive(Serialize, Debug)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub path: String,
+    pub prompts: Vec<String>, // Labels of `{{prompt:Label}}` placeholders the UI must ask for
+    pub tags: Vec<String>,
+    pub fields: Mapping, // Other frontmatter presets, e.g. `type: meeting`
+}
+
+/// A template with its placeholders filled in.
+#[derive(Debug)]
+pub struct RenderedTemplate {
+    pub tags: Vec<String>,
+    pub fields: Mapping,
+    pub body: String,
+}
+
+#[tauri::command]
+pub fn list_templates(state: State<'_, VaultState>) -> Result<Vec<TemplateInfo>, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let templates_dir = vault_path.join(TEMPLATES_DIR);
+    if !templates_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let pattern = templates_dir.join("**/*.md");
+    let pattern_str = pattern.to_str().ok_or("Invalid vault path")?;
+
+    let mut templates = Vec::new();
+    for path in glob::glob(pattern_str).map_err(|e| e.to_string())?.flatten() {
+        let template_path = VaultPath::from_full_path(&vault_path, &path)?;
+        let raw = fs::read_to_string(&template_path).map_err(|e| e.to_string())?;
+        let rendered = split_template(&raw);
+        let name = template_path
+            .relative()
+            .strip_prefix(TEMPLATES_DIR)
+            .map_err(|e| e.to_string())?
+            .with_extension("");
+
+        templates.push(TemplateInfo {
+            name: name.to_string_lossy().to_string(),
+            path: template_path.relative().to_string_lossy().to_string(),
+            prompts: find_prompts(&raw),
+            tags: rendered.tags,
+            fields: rendered.fields,
+        });
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// Creates a note from `_templates/<template>.md`. `values` answers the template's prompts.
+#[tauri::command]
+pub fn create_note_from_template(
+    state: State<'_, VaultState>,
+    template: String,
+    title: String,
+    values: Option<HashMap<String, String>>,
+    tags: Option<Vec<String>>
+) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let template_path = VaultPath::new(&vault_path, &format!("{}/{}.md", TEMPLATES_DIR, template))?;
+
+    let note_path = unique_note_path(&vault_path, "", &title, None)?;
+
+    let raw = fs::read_to_string(&template_path).map_err(|e| format!("Template {}: {}", template, e))?;
+    let rendered = render_template(&raw, &title, &values.unwrap_or_default(), Local::now());
+
+    let mut metadata = new_metadata(&title, tags.unwrap_or_default());
+    apply_presets(&mut metadata, &rendered);
+    save_note(&vault_path, &note_path, render_note(&metadata, &rendered.body)?)?;
+
+    Ok(note_path.relative().to_string_lossy().to_string())
+}
+
+/// Renders the template file at `template_path` for a new note dated `now`.
+pub fn instantiate_template(
+    vault_path: &Path,
+    template_path: &str,
+    title: &str,
+    now: DateTime<Local>
+) -> Result<RenderedTemplate, String> {
+    let raw = fs::read_to_string(VaultPath::new(vault_path, template_path)?).map_err(|e| e.to_string())?;
+    Ok(render_template(&raw, title, &HashMap::new(), now))
+}
+
+/// Copies template presets onto a freshly created note's metadata.
+pub fn apply_presets(metadata: &mut NoteMetadata, rendered: &RenderedTemplate) {
+    for tag in &rendered.tags {
+        if !metadata.tags.contains(tag) {
+            metadata.tags.push(tag.clone());
+        }
+    }
+    for (key, value) in &rendered.fields {
+        metadata.extra.insert(key.clone(), value.clone());
+    }
+}
+
+/// Fills `{{date}}`, `{{time}}`, `{{title}}`, `{{device}}` and `{{prompt:Label}}`
+/// placeholders in the body and in string frontmatter values.
+/// `{{date:%d/%m/%Y}}` and `{{time:%H:%M:%S}}` take a custom chrono format.
+pub fn render_template(
+    raw: &str,
+    title: &str,
+    values: &HashMap<String, String>,
+    now: DateTime<Local>
+) -> RenderedTemplate {
+    let mut template = split_template(raw);
+    template.body = substitute(&template.body, title, values, now);
+    template.tags = template.tags.iter().map(|tag| substitute(tag, title, values, now)).collect();
+    for (_, value) in template.fields.iter_mut() {
+        if let Value::String(text) = value {
+            *text = substitute(text, title, values, now);
+        }
+    }
+    template
+}
+
+fn split_template(raw: &str) -> RenderedTemplate {
+    let Some((yaml, body)) = split_frontmatter(raw) else {
+        return RenderedTemplate { tags: Vec::new(), fields: Mapping::new(), body: raw.to_string() };
+    };
+    let body = body.strip_prefix("\r\n").or_else(|| body.strip_prefix('\n')).unwrap_or(body);
+
+    let mut fields: Mapping = serde_yaml::from_str(yaml).unwrap_or_default();
+    let tags = match fields.remove("tags") {
+        Some(Value::Sequence(tags)) => tags.iter().filter_map(|t| t.as_str().map(str::to_string)).collect(),
+        Some(Value::String(tag)) => vec![tag],
+        _ => Vec::new(),
+    };
+    for key in RESERVED_FIELDS {
+        fields.remove(key);
+    }
+
+    RenderedTemplate { tags, fields, body: body.to_string() }
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*(\w+)(?::([^}]*))?\s*\}\}").unwrap()
+}
+
+fn substitute(text: &str, title: &str, values: &HashMap<String, String>, now: DateTime<Local>) -> String {
+    placeholder_regex()
+        .replace_all(text, |cap: &regex::Captures| {
+            let argument = cap.get(2).map(|m| m.as_str().trim());
+            match (&cap[1], argument) {
+                ("date", format) => try_format(now.format(format.unwrap_or("%Y-%m-%d"))).unwrap_or_else(|| cap[0].to_string()),
+                ("time", format) => try_format(now.format(format.unwrap_or("%H:%M"))).unwrap_or_else(|| cap[0].to_string()),
+                ("title", _) => title.to_string(),
+                ("device", _) => current_device_name(),
+                ("prompt", Some(label)) => values.get(label).cloned().unwrap_or_default(),
+                // Unknown placeholders are kept for the user to fill in
+                _ => cap[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+fn find_prompts(raw: &str) -> Vec<String> {
+    let mut prompts = Vec::new();
+    for cap in placeholder_regex().captures_iter(raw) {
+        if let ("prompt", Some(label)) = (&cap[1], cap.get(2)) {
+            let label = label.as_str().trim().to_string();
+            if !prompts.contains(&label) {
+                prompts.push(label);
+            }
+        }
+    }
+    prompts
+}
@@ -1,7 +1,40 @@
 #[cfg(test)]
 mod verification_tests {
-    use crate::vault_manager::sanitize_filename;
-    use crate::graph_engine::extract_links;
+    use crate::filename_policy::{sanitize_filename, unique_note_path};
+    use crate::vault_manager::{parse_deep_link, rename_note_at, NoteIndex};
+    use crate::vault_manager::{parse_note, render_note, bump_version, save_file, save_note, recover_vault, VaultPath};
+    use crate::vault_manager::{paginate_notes, ListNotesQuery, NoteSort, NoteSummary, append_under_heading, new_metadata};
+    use crate::sync_engine::{NoteSyncMetadata, SyncEngine};
+    use crate::trash_manager::{load_tombstones, move_to_trash, purge_expired};
+    use crate::template_manager::{apply_presets, render_template};
+    use crate::history_manager::{diff_text, DiffChunk, DiffMode, DiffOp};
+    use crate::attachment_manager::{find_unreferenced_attachments, import_attachment_file, store_attachment};
+    use crate::graph_metrics::{rank_notes, Metric};
+    use crate::graph_store::{GraphStore, IndexedNote};
+    use crate::wikilink::{normalize_name, parse_note_links, LinkSyntax, WikiLink};
+    use crate::graph_engine::{assemble_graph, is_attachment_target, refresh_cache, GraphCache, LinkKind, NodeKind, RebuildStats};
+    use crate::frontmatter::{apply_patch, Document};
+    use crate::device_identity::load_or_create_identity;
+    use crate::batch_manager::{revert_batch, run_batch, BatchOperation};
+    use crate::folder_manager::{build_folder_tree, make_folder, move_note_to, rename_folder_at};
+    use crate::vault_registry::{load_registry, remember_vault};
+    use crate::migration_manager::{run_migrations, SCHEMA_VERSION};
+    use crate::vault_config::load_config;
+    use crate::vault_doctor::{apply_fix, diagnose_vault, DoctorFix, IssueKind};
+    use crate::vault_watcher::{classify_events, ChangeKind, VaultChange};
+    use notify_debouncer_full::notify::event::{CreateKind, ModifyKind, RenameMode};
+    use notify_debouncer_full::notify::{Event, EventKind};
+    use std::path::PathBuf;
+    use std::collections::{HashMap, HashSet};
+    use std::fs;
+    use crate::graph_engine::{extract_links, rewrite_wikilinks};
+
+    /// Fresh, empty vault directory under the system temp dir.
+    fn temp_vault() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("secretariat-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
 
     // RUST-02: Filename Sanitization
     #[test]
@@ -35,4 +68,828 @@ mod verification_tests {
         assert!(links.contains(&"Note C".to_string()));
         assert_eq!(links.len(), 3);
     }
+
+    // Note editing: optimistic version checking
+    #[test]
+    fn test_note_round_trip_and_version_bump() {
+        let raw = "---\nid: abc\ntitle: Note A\ncreated_at: 2024-01-01T00:00:00Z\nversion: 3\ntags: [work]\n---\n\n# Note A\nBody\n";
+        let (mut metadata, body) = parse_note(raw).expect("note should parse");
+        assert_eq!(metadata.version, 3);
+        assert_eq!(body, "# Note A\nBody\n");
+
+        assert!(bump_version(&mut metadata, 2).is_err(), "stale editor version must be rejected");
+        assert_eq!(metadata.version, 3);
+
+        bump_version(&mut metadata, 3).unwrap();
+        assert_eq!(metadata.version, 4);
+        assert!(!metadata.updated_at.is_empty());
+
+        let rendered = render_note(&metadata, &body).unwrap();
+        let (reparsed, reparsed_body) = parse_note(&rendered).unwrap();
+        assert_eq!(reparsed.version, 4);
+        assert_eq!(reparsed.tags, vec!["work".to_string()]);
+        assert_eq!(reparsed_body, body);
+    }
+
+    // Atomic writes: the vault is never left with half-written files
+    #[test]
+    fn test_atomic_save_and_recovery() {
+        let vault = temp_vault();
+        let note = vault.join("Note.md");
+
+        save_file(&note, "first").unwrap();
+        save_file(&note, "second").unwrap();
+        assert_eq!(fs::read_to_string(&note).unwrap(), "second");
+        assert_eq!(fs::read_dir(&vault).unwrap().count(), 1, "no temp files may be left behind");
+
+        // Simulate a crash between temp write and rename
+        fs::create_dir_all(vault.join("sub")).unwrap();
+        fs::write(vault.join("sub/.Other.md.1234.secretariat-tmp"), "partial").unwrap();
+        assert_eq!(recover_vault(&vault).unwrap(), 1);
+        assert!(!vault.join("sub/.Other.md.1234.secretariat-tmp").exists());
+        assert_eq!(fs::read_to_string(&note).unwrap(), "second");
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Vault confinement: untrusted paths from the webview cannot leave the vault
+    #[test]
+    fn test_vault_path_confinement() {
+        let vault = temp_vault();
+        fs::create_dir_all(vault.join("Projects")).unwrap();
+
+        assert!(VaultPath::new(&vault, "Note.md").is_ok());
+        assert!(VaultPath::new(&vault, "Projects/./Plan.md").is_ok());
+        assert!(VaultPath::new(&vault, "../../.ssh/id_rsa").is_err());
+        assert!(VaultPath::new(&vault, "Projects/../../secret.md").is_err());
+        assert!(VaultPath::new(&vault, "/etc/passwd").is_err());
+        assert!(VaultPath::new(&vault, "").is_err());
+
+        let resolved = VaultPath::new(&vault, "Projects/Plan.md").unwrap();
+        assert_eq!(resolved.relative(), std::path::Path::new("Projects/Plan.md"));
+
+        #[cfg(unix)]
+        {
+            let outside = temp_vault();
+            std::os::unix::fs::symlink(&outside, vault.join("escape")).unwrap();
+            assert!(VaultPath::new(&vault, "escape/stolen.md").is_err());
+            fs::remove_dir_all(&outside).unwrap();
+        }
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Renames: inbound links follow the note
+    #[test]
+    fn test_rewrite_wikilinks_on_rename() {
+        let content = "See [[Old Note]], [[old note|the old one]] and [[Old Note#Budget]].\nUnrelated [[Other]].";
+        let (rewritten, count) = rewrite_wikilinks(content, &["Old Note".to_string()], "New Note");
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            rewritten,
+            "See [[New Note]], [[New Note|the old one]] and [[New Note#Budget]].\nUnrelated [[Other]]."
+        );
+
+        let content = "| [[Old Note\\|tbl]] |\n\nLiteral `[[Old Note]]`\n\n~~~\n[[Old Note]]\n~~~\n";
+        let (rewritten, count) = rewrite_wikilinks(content, &["Old Note".to_string()], "New Note");
+        assert_eq!((rewritten.as_str(), count), ("| [[New Note\\|tbl]] |\n\nLiteral `[[Old Note]]`\n\n~~~\n[[Old Note]]\n~~~\n", 1));
+    }
+
+    #[test]
+    fn test_rename_rewrites_path_qualified_links() {
+        let vault = temp_vault();
+        fs::create_dir(vault.join("Projects")).unwrap();
+        fs::write(vault.join("Projects/Old Note.md"), render_note(&new_metadata("Old Note", Vec::new()), "Body").unwrap()).unwrap();
+        fs::write(vault.join("Index.md"), "[[Projects/Old Note#Goals|plan]], [[Old Note]] and [it](Projects/Old%20Note.md)\n").unwrap();
+
+        let report = rename_note_at(&vault, "Projects/Old Note.md", "New Note".to_string()).unwrap();
+        assert_eq!((report.new_filename.as_str(), report.links_rewritten), ("Projects/New Note.md", 3));
+        assert_eq!(
+            fs::read_to_string(vault.join("Index.md")).unwrap(),
+            "[[Projects/New Note#Goals|plan]], [[New Note]] and [it](Projects/New%20Note.md)\n"
+        );
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    #[test]
+    fn test_rename_leaves_links_to_other_notes() {
+        let vault = temp_vault();
+        fs::write(vault.join("A.md"), render_note(&new_metadata("Plan", Vec::new()), "Body").unwrap()).unwrap();
+        fs::write(vault.join("Plan.md"), render_note(&new_metadata("Roadmap", Vec::new()), "Body").unwrap()).unwrap();
+        fs::write(vault.join("Src.md"), "[[Plan]] and [[A]]\n").unwrap();
+
+        // `[[Plan]]` names Plan.md: a file name outranks A's title
+        let report = rename_note_at(&vault, "A.md", "Agenda".to_string()).unwrap();
+        assert_eq!(report.links_rewritten, 1);
+        assert_eq!(fs::read_to_string(vault.join("Src.md")).unwrap(), "[[Plan]] and [[Agenda]]\n");
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Soft delete: files go to the trash and leave a tombstone for sync
+    #[test]
+    fn test_delete_moves_to_trash_with_tombstone() {
+        let vault = temp_vault();
+        let raw = "---\nid: note-1\ntitle: Doomed\ncreated_at: 2024-01-01T00:00:00Z\nversion: 2\n---\n\nBody\n";
+        fs::write(vault.join("Doomed.md"), raw).unwrap();
+
+        let entry = move_to_trash(&vault, &VaultPath::new(&vault, "Doomed.md").unwrap()).unwrap();
+        assert!(!vault.join("Doomed.md").exists());
+        assert_eq!(entry.note_id.as_deref(), Some("note-1"));
+        assert_eq!(entry.original_path, "Doomed.md");
+        assert!(vault.join(".trash").join(&entry.entry_id).join("Doomed.md").exists());
+
+        let tombstones = load_tombstones(&vault).unwrap();
+        assert_eq!(tombstones["note-1"].version, 3);
+
+        // The trash is invisible to vault scans
+        let (local_index, paths) = SyncEngine::scan_local_vault(&vault).unwrap();
+        assert!(paths.is_empty());
+        assert!(local_index["note-1"].deleted);
+
+        // A hand-edited date never gets the file purged
+        let index = vault.join(".trash/index.json");
+        fs::write(&index, fs::read_to_string(&index).unwrap().replace(&entry.deleted_at, "last week")).unwrap();
+        let report = purge_expired(&vault, 0).unwrap();
+        assert_eq!((report.purged, report.unreadable), (0, vec!["Doomed.md".to_string()]));
+        assert!(vault.join(".trash").join(&entry.entry_id).join("Doomed.md").exists());
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    #[test]
+    fn test_sync_plan_respects_tombstones() {
+        let meta = |id: &str, version: u64, deleted: bool| NoteSyncMetadata {
+            id: id.to_string(),
+            updated_at: String::new(),
+            version,
+            deleted,
+            device_id: "laptop".to_string(),
+        };
+
+        let local: HashMap<String, NoteSyncMetadata> = [
+            ("deleted-here".to_string(), meta("deleted-here", 3, true)),
+            ("deleted-there".to_string(), meta("deleted-there", 2, false)),
+        ].into_iter().collect();
+        let remote: HashMap<String, NoteSyncMetadata> = [
+            ("deleted-here".to_string(), meta("deleted-here", 2, false)),
+            ("deleted-there".to_string(), meta("deleted-there", 3, true)),
+            ("gone-everywhere".to_string(), meta("gone-everywhere", 5, true)),
+        ].into_iter().collect();
+
+        let plan = SyncEngine::calculate_sync_plan(&local, &remote);
+        assert_eq!(plan.to_upload, vec!["deleted-here".to_string()]);
+        assert_eq!(plan.to_delete, vec!["deleted-there".to_string()]);
+        assert!(plan.to_download.is_empty(), "tombstoned notes must not be downloaded");
+
+        // Two devices both moved a note from version 4 to 5
+        let edited = |device: &str, updated_at: &str| NoteSyncMetadata {
+            updated_at: updated_at.to_string(),
+            device_id: device.to_string(),
+            ..meta("shared", 5, false)
+        };
+        let local = [("shared".to_string(), edited("laptop", "2024-05-01T10:00:00Z"))].into_iter().collect();
+        let remote = [("shared".to_string(), edited("phone", "2024-05-01T10:02:00Z"))].into_iter().collect();
+        let plan = SyncEngine::calculate_sync_plan(&local, &remote);
+        assert_eq!(plan.conflicts, vec!["shared".to_string()]);
+        assert!(plan.to_upload.is_empty());
+    }
+
+    // Sidebar listing: filters and cursor pagination
+    #[test]
+    fn test_list_notes_pagination() {
+        let summary = |title: &str, path: &str, tags: &[&str]| NoteSummary {
+            id: Some(title.to_lowercase()),
+            title: title.to_string(),
+            path: path.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created_at: None,
+            updated_at: None,
+            word_count: 0,
+        };
+        let notes = vec![
+            summary("Charlie", "Charlie.md", &["#work"]),
+            summary("Alpha", "Projects/Alpha.md", &["work"]),
+            summary("Bravo", "Projects/Bravo.md", &["home"]),
+            summary("Delta", "Delta.md", &["Work"]),
+        ];
+
+        let mut query = ListNotesQuery { sort_by: NoteSort::Title, limit: Some(2), ..Default::default() };
+        let first = paginate_notes(notes.clone(), &query).unwrap();
+        assert_eq!(first.total, 4);
+        assert_eq!(first.notes.iter().map(|n| n.title.as_str()).collect::<Vec<_>>(), vec!["Alpha", "Bravo"]);
+
+        query.cursor = first.next_cursor;
+        let second = paginate_notes(notes.clone(), &query).unwrap();
+        assert_eq!(second.notes.iter().map(|n| n.title.as_str()).collect::<Vec<_>>(), vec!["Charlie", "Delta"]);
+        assert!(second.next_cursor.is_none());
+
+        let work = ListNotesQuery { tag: Some("work".to_string()), folder: Some("Projects".to_string()), ..Default::default() };
+        let filtered = paginate_notes(notes, &work).unwrap();
+        assert_eq!(filtered.notes.len(), 1);
+        assert_eq!(filtered.notes[0].title, "Alpha");
+    }
+
+    // Daily notes: entries land at the end of the chosen section
+    #[test]
+    fn test_append_under_heading() {
+        let body = "# 2024-05-01\n\n## Log\n- 09:00 Standup\n\n## Tasks\n- [ ] Ship\n";
+        assert_eq!(
+            append_under_heading(body, "Log", "- 10:30 Call with Ana"),
+            "# 2024-05-01\n\n## Log\n- 09:00 Standup\n- 10:30 Call with Ana\n\n## Tasks\n- [ ] Ship\n"
+        );
+
+        // Missing sections are created at the end
+        assert_eq!(
+            append_under_heading("# 2024-05-01\n", "Journal", "- 22:00 Good day"),
+            "# 2024-05-01\n\n## Journal\n- 22:00 Good day\n"
+        );
+
+        // Headings inside code blocks are not sections
+        let with_code = "```\n## Log\n```\n";
+        assert!(append_under_heading(with_code, "Log", "- entry").ends_with("```\n\n## Log\n- entry\n"));
+        let with_tildes = "~~~\n## Log\n~~~\n";
+        assert!(append_under_heading(with_tildes, "Log", "- entry").ends_with("~~~\n\n## Log\n- entry\n"));
+
+        // Windows line endings survive
+        assert_eq!(
+            append_under_heading("## Log\r\n- a\r\n\r\n## Tasks\r\n", "Log", "- b"),
+            "## Log\r\n- a\r\n- b\r\n\r\n## Tasks\r\n"
+        );
+    }
+
+    // Templates: placeholders and frontmatter presets
+    #[test]
+    fn test_render_template() {
+        let template = "---\ntags: [meeting]\ntype: meeting\nproject: \"{{prompt:Project}}\"\nid: ignored\n---\n\n# {{title}}\nDate: {{date:%d/%m/%Y}}\nAttendees: {{prompt:Attendees}}\n{{unknown}}\n";
+        let values: HashMap<String, String> = [
+            ("Project".to_string(), "Apollo".to_string()),
+            ("Attendees".to_string(), "Ana, Bruno".to_string()),
+        ].into_iter().collect();
+        let now = chrono::TimeZone::with_ymd_and_hms(&chrono::Local, 2024, 5, 1, 9, 30, 0).unwrap();
+
+        let rendered = render_template(template, "Kickoff", &values, now);
+        assert_eq!(rendered.body, "# Kickoff\nDate: 01/05/2024\nAttendees: Ana, Bruno\n{{unknown}}\n");
+        assert_eq!(rendered.tags, vec!["meeting".to_string()]);
+        assert_eq!(rendered.fields.get("type").and_then(|v| v.as_str()), Some("meeting"));
+        assert_eq!(rendered.fields.get("project").and_then(|v| v.as_str()), Some("Apollo"));
+        assert!(rendered.fields.get("id").is_none(), "reserved fields cannot be preset");
+
+        // Presets survive later edits of the note
+        let mut metadata = new_metadata("Kickoff", Vec::new());
+        apply_presets(&mut metadata, &rendered);
+        let (reparsed, _) = parse_note(&render_note(&metadata, &rendered.body).unwrap()).unwrap();
+        assert_eq!(reparsed.extra.get("type").and_then(|v| v.as_str()), Some("meeting"));
+        assert_eq!(reparsed.tags, vec!["meeting".to_string()]);
+    }
+
+    // Revision history: every overwrite keeps the previous content
+    #[test]
+    fn test_save_note_snapshots_previous_revision() {
+        let vault = temp_vault();
+        let note_path = VaultPath::new(&vault, "Note.md").unwrap();
+        let revision = |version: u64| format!("---\nid: note-1\ntitle: Note\ncreated_at: 2024-01-01T00:00:00Z\nversion: {}\n---\n\nv{}\n", version, version);
+
+        save_note(&vault, &note_path, revision(1)).unwrap();
+        save_note(&vault, &note_path, revision(2)).unwrap();
+        save_note(&vault, &note_path, revision(3)).unwrap();
+
+        let history = vault.join(".secretariat/history/note-1");
+        let mut snapshots: Vec<String> = fs::read_dir(&history).unwrap()
+            .map(|e| fs::read_to_string(e.unwrap().path()).unwrap())
+            .collect();
+        snapshots.sort();
+        assert_eq!(snapshots, vec![revision(1), revision(2)]);
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    #[test]
+    fn test_diff_text_modes() {
+        let lines = diff_text("a\nb\nc\n", "a\nB\nc\n", DiffMode::Line);
+        assert_eq!(lines, vec![
+            DiffChunk { op: DiffOp::Equal, text: "a\n".to_string() },
+            DiffChunk { op: DiffOp::Delete, text: "b\n".to_string() },
+            DiffChunk { op: DiffOp::Insert, text: "B\n".to_string() },
+            DiffChunk { op: DiffOp::Equal, text: "c\n".to_string() },
+        ]);
+
+        let words = diff_text("the quick fox", "the slow fox", DiffMode::Word);
+        assert!(words.contains(&DiffChunk { op: DiffOp::Delete, text: "quick".to_string() }));
+        assert!(words.contains(&DiffChunk { op: DiffOp::Insert, text: "slow".to_string() }));
+    }
+
+    // Attachments: content-addressed storage and orphan detection
+    #[test]
+    fn test_attachments_dedup_and_cleanup() {
+        let vault = temp_vault();
+
+        let first = store_attachment(&vault, "Voice memo.m4a", b"audio bytes").unwrap();
+        let again = store_attachment(&vault, "copy.m4a", b"audio bytes").unwrap();
+        assert!(!first.deduplicated);
+        assert!(again.deduplicated);
+        assert_eq!(first.path, again.path);
+        assert_eq!(first.embed, format!("![[{}]]", first.file_name));
+
+        // Picked files are read from disk, not sent over IPC
+        let outside = temp_vault();
+        fs::write(outside.join("Dropped.m4a"), b"audio bytes").unwrap();
+        let picked = import_attachment_file(&vault, &outside.join("Dropped.m4a")).unwrap();
+        assert!(picked.deduplicated);
+        assert_eq!(picked.path, first.path);
+        assert!(import_attachment_file(&vault, &outside.join("Missing.m4a")).is_err());
+        fs::remove_dir_all(&outside).unwrap();
+
+        let orphan = store_attachment(&vault, "scan.pdf", b"pdf bytes").unwrap();
+        fs::write(vault.join("Note.md"), format!("Recording: {}\n", first.embed)).unwrap();
+        assert_eq!(find_unreferenced_attachments(&vault).unwrap(), vec![orphan.path]);
+
+        assert!(is_attachment_target(&first.file_name));
+        assert!(is_attachment_target("scan.pdf|200"));
+        assert!(!is_attachment_target("Note B"));
+        assert!(!is_attachment_target("Note B.md"));
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Frontmatter: hand-written files survive edits made by the app
+    #[test]
+    fn test_frontmatter_round_trip() {
+        let raw = "---\nid: abc\ncreated_at: 2024-01-01T00:00:00Z\n# kept while untouched\nzeta: 1\ntitle: Hand written\nversion: 3\naliases: [HW]\n---\n\nBody  with trailing spaces  \r\n";
+
+        let document = Document::parse(raw).unwrap();
+        assert_eq!(document.render().unwrap(), raw);
+        assert_eq!(document.content(), "Body  with trailing spaces  \r\n");
+
+        let mut document = Document::parse(raw).unwrap();
+        let mut metadata = document.metadata().unwrap();
+        bump_version(&mut metadata, 3).unwrap();
+        document.set_metadata(&metadata).unwrap();
+        let rendered = document.render().unwrap();
+
+        let keys: Vec<&str> = document.fields().keys().filter_map(|k| k.as_str()).collect();
+        assert_eq!(&keys[..5], ["id", "created_at", "zeta", "title", "version"]);
+        assert!(rendered.ends_with("---\n\nBody  with trailing spaces  \r\n"));
+        assert_eq!(parse_note(&rendered).unwrap().0.version, 4);
+    }
+
+    #[test]
+    fn test_frontmatter_patch() {
+        let mut document = Document::parse("---\nid: abc\ntitle: T\ncreated_at: x\nstatus: draft\n---\n\nBody\n").unwrap();
+
+        let patch = serde_json::json!({ "status": null, "priority": 2 });
+        apply_patch(&mut document, patch.as_object().unwrap()).unwrap();
+        assert!(!document.fields().contains_key("status"));
+        assert_eq!(document.fields().get("priority"), Some(&serde_yaml::Value::from(2)));
+        assert_eq!(document.content(), "Body\n");
+
+        let reserved = serde_json::json!({ "version": 10 });
+        assert!(apply_patch(&mut document, reserved.as_object().unwrap()).is_err());
+    }
+
+    // Watcher: raw filesystem events become vault-relative changes
+    #[test]
+    fn test_classify_watcher_events() {
+        let root = PathBuf::from("/vault");
+        let events = vec![
+            Event::new(EventKind::Create(CreateKind::File)).add_path(root.join(".Note.md.1234.secretariat-tmp")),
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+                .add_path(root.join("Old.md"))
+                .add_path(root.join("Projects/New.md")),
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+                .add_path(root.join("Gone.md"))
+                .add_path(root.join(".trash/abc/Gone.md")),
+            Event::new(EventKind::Modify(ModifyKind::Any)).add_path(root.join("Note.md")),
+            Event::new(EventKind::Modify(ModifyKind::Any)).add_path(root.join("Note.md")),
+            Event::new(EventKind::Create(CreateKind::File)).add_path(PathBuf::from("/elsewhere/x.md")),
+        ];
+
+        assert_eq!(classify_events(&[root], &events), vec![
+            VaultChange { kind: ChangeKind::Renamed, path: "Projects/New.md".to_string(), from: Some("Old.md".to_string()) },
+            VaultChange { kind: ChangeKind::Removed, path: "Gone.md".to_string(), from: None },
+            VaultChange { kind: ChangeKind::Modified, path: "Note.md".to_string(), from: None },
+        ]);
+    }
+
+    // Vault registry: most recent first, no duplicates
+    #[test]
+    fn test_recent_vaults() {
+        let dir = temp_vault();
+        let registry = dir.join("app-data/vaults.json");
+
+        remember_vault(&registry, &dir.join("Work")).unwrap();
+        remember_vault(&registry, &dir.join("Personal")).unwrap();
+        let entry = remember_vault(&registry, &dir.join("Work")).unwrap();
+        assert_eq!(entry.name, "Work");
+
+        let names: Vec<String> = load_registry(&registry).unwrap().recent.into_iter().map(|v| v.name).collect();
+        assert_eq!(names, vec!["Work", "Personal"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Device identity: generated once, stable afterwards
+    #[test]
+    fn test_device_identity_is_persistent() {
+        let dir = temp_vault();
+        let path = dir.join("app-data/device.json");
+
+        let first = load_or_create_identity(&path).unwrap();
+        let again = load_or_create_identity(&path).unwrap();
+        assert_eq!(first, again);
+        assert!(uuid::Uuid::parse_str(&first.device_id).is_ok());
+        assert!(!first.name.is_empty());
+
+        // A damaged file is set aside and a new identity takes its place
+        fs::write(&path, "{ not json").unwrap();
+        let fresh = load_or_create_identity(&path).unwrap();
+        assert_ne!(fresh.device_id, first.device_id);
+        assert_eq!(load_or_create_identity(&path).unwrap(), fresh);
+        let backups: Vec<_> = fs::read_dir(dir.join("app-data")).unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with("device.json.corrupt-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(backups[0].path()).unwrap(), "{ not json");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Folders: moves and renames stay inside the vault, counts roll up
+    #[test]
+    fn test_folder_operations() {
+        let vault = temp_vault();
+        fs::write(vault.join("Inbox.md"), "[plan](Plan.md#Goals)").unwrap();
+        fs::write(vault.join("Plan.md"), "[inbox](./Inbox.md)").unwrap();
+
+        assert_eq!(make_folder(&vault, "Projects/2024").unwrap(), "Projects/2024");
+        assert!(make_folder(&vault, ".secretariat/notes").is_err());
+        assert!(make_folder(&vault, "../outside").is_err());
+
+        assert_eq!(move_note_to(&vault, "Plan.md", "Projects/2024").unwrap(), "Projects/2024/Plan.md");
+        assert!(move_note_to(&vault, "Inbox.md", "../").is_err());
+        assert_eq!(fs::read_to_string(vault.join("Inbox.md")).unwrap(), "[plan](Projects/2024/Plan.md#Goals)");
+        assert_eq!(fs::read_to_string(vault.join("Projects/2024/Plan.md")).unwrap(), "[inbox](../../Inbox.md)");
+        assert_eq!(rename_folder_at(&vault, "Projects/2024", "Archive").unwrap(), "Projects/Archive");
+        assert!(vault.join("Projects/Archive/Plan.md").exists());
+        assert_eq!(fs::read_to_string(vault.join("Inbox.md")).unwrap(), "[plan](Projects/Archive/Plan.md#Goals)");
+        assert_eq!(fs::read_to_string(vault.join("Projects/Archive/Plan.md")).unwrap(), "[inbox](../../Inbox.md)");
+
+        let tree = build_folder_tree(&vault).unwrap();
+        assert_eq!((tree.note_count, tree.total_notes), (1, 2));
+        let projects = &tree.children[0];
+        assert_eq!(projects.path, "Projects");
+        assert_eq!((projects.note_count, projects.total_notes), (0, 1));
+        assert_eq!(projects.children[0].name, "Archive");
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Filename policy: portable names, collisions get a suffix
+    #[test]
+    fn test_filename_policy() {
+        assert_eq!(sanitize_filename("CON"), "CON_.md");
+        assert_eq!(sanitize_filename("aux.notes"), "aux_.notes.md");
+        assert_eq!(sanitize_filename("Ends with dots... "), "Ends with dots.md");
+        assert_eq!(sanitize_filename(".hidden"), "hidden.md");
+        assert_eq!(sanitize_filename("Say \"hi\"\n"), "Say _hi_.md");
+        assert_eq!(sanitize_filename("Cafe\u{301}"), "Caf\u{e9}.md");
+        assert_eq!(sanitize_filename("..."), "Untitled.md");
+        assert!(sanitize_filename(&"é".repeat(200)).len() <= 123);
+
+        let vault = temp_vault();
+        fs::write(vault.join("note.md"), "x").unwrap();
+        let path = unique_note_path(&vault, "", "Note", None).unwrap();
+        assert_eq!(path.relative().to_str(), Some("Note (1).md"));
+
+        // A note being renamed may keep its own name, in any case
+        let current = VaultPath::new(&vault, "note.md").unwrap();
+        let path = unique_note_path(&vault, "", "NOTE", Some(&current)).unwrap();
+        assert_eq!(path.relative().to_str(), Some("NOTE.md"));
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Id index: lookups follow a note through renames and moves
+    #[test]
+    fn test_note_index_follows_renames() {
+        let vault = temp_vault();
+        let metadata = new_metadata("Plan", Vec::new());
+        fs::write(vault.join("Plan.md"), render_note(&metadata, "Body").unwrap()).unwrap();
+        let vault = fs::canonicalize(&vault).unwrap();
+
+        let mut index = NoteIndex::default();
+        assert_eq!(index.resolve(&vault, &metadata.id).unwrap().relative().to_str(), Some("Plan.md"));
+
+        fs::create_dir(vault.join("Archive")).unwrap();
+        fs::rename(vault.join("Plan.md"), vault.join("Archive/Old plan.md")).unwrap();
+        assert_eq!(index.resolve(&vault, &metadata.id).unwrap().relative().to_str(), Some("Archive/Old plan.md"));
+        assert!(index.resolve(&vault, "missing").is_err());
+
+        let link = format!("secretariat://note/{}", metadata.id);
+        assert_eq!(parse_deep_link(&link).unwrap(), metadata.id);
+        assert!(parse_deep_link("secretariat://note/../etc").is_err());
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Batches: dry run writes nothing, undo reverts every file
+    #[test]
+    fn test_batch_apply_and_undo() {
+        let vault = temp_vault();
+        let mut paths = Vec::new();
+        for (title, tags) in [("One", vec![]), ("Two", vec!["Work".to_string()])] {
+            let file = format!("{}.md", title);
+            fs::write(vault.join(&file), render_note(&new_metadata(title, tags), "Body\n").unwrap()).unwrap();
+            paths.push(VaultPath::new(&vault, &file).unwrap());
+        }
+        let original = fs::read_to_string(vault.join("One.md")).unwrap();
+        let add_tag = BatchOperation::AddTag { tag: "#work".to_string() };
+
+        let preview = run_batch(&vault, &paths, &add_tag, true).unwrap();
+        assert_eq!((preview.changes.len(), preview.unchanged, preview.batch_id), (1, 1, None));
+        assert_eq!(fs::read_to_string(vault.join("One.md")).unwrap(), original);
+
+        let applied = run_batch(&vault, &paths, &add_tag, false).unwrap();
+        let (metadata, _) = parse_note(&fs::read_to_string(vault.join("One.md")).unwrap()).unwrap();
+        assert_eq!((metadata.tags, metadata.version), (vec!["work".to_string()], 2));
+
+        let undo = revert_batch(&vault, applied.batch_id.as_deref().unwrap()).unwrap();
+        assert_eq!(undo.restored, vec!["One.md".to_string()]);
+        let (metadata, body) = parse_note(&fs::read_to_string(vault.join("One.md")).unwrap()).unwrap();
+        assert!(metadata.tags.is_empty());
+        assert_eq!((metadata.version, body.as_str()), (3, "Body\n"));
+
+        // Links follow the notes out and back
+        fs::write(vault.join("Index.md"), "[x](Two.md)").unwrap();
+        let moved = run_batch(&vault, &paths, &BatchOperation::MoveToFolder { folder: "Archive".to_string() }, false).unwrap();
+        assert!(vault.join("Archive/Two.md").exists());
+        assert_eq!(fs::read_to_string(vault.join("Index.md")).unwrap(), "[x](Archive/Two.md)");
+        revert_batch(&vault, moved.batch_id.as_deref().unwrap()).unwrap();
+        assert!(vault.join("One.md").exists() && vault.join("Two.md").exists());
+        assert_eq!(fs::read_to_string(vault.join("Index.md")).unwrap(), "[x](Two.md)");
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Vault doctor: finds what sync would silently skip, and repairs it
+    #[test]
+    fn test_vault_doctor_repairs_notes() {
+        let vault = temp_vault();
+        let stress = "---\nid: stress-1\ntitle: \"Note 1\"\ncreated_at: 2024-01-01T00:00:00Z\ntags: []\n---\nSee [[Note 1]] and [[Missing]]\n";
+        fs::write(vault.join("Note_1.md"), stress).unwrap();
+        fs::write(vault.join("Copy.md"), stress).unwrap();
+        fs::write(vault.join("plain.md"), "No frontmatter here").unwrap();
+        fs::write(vault.join("legacy.md"), b"caf\xe9").unwrap();
+
+        let report = diagnose_vault(&vault).unwrap();
+        let kinds = |kind| report.issues.iter().filter(|issue| issue.kind == kind).count();
+        assert_eq!(report.files_checked, 4);
+        assert_eq!((kinds(IssueKind::IncompleteFrontmatter), kinds(IssueKind::DuplicateId)), (2, 1));
+        assert_eq!((kinds(IssueKind::MissingFrontmatter), kinds(IssueKind::NonUtf8)), (1, 1));
+        assert_eq!(kinds(IssueKind::BrokenLink), 2); // [[Missing]] from both copies
+
+        for issue in &report.issues {
+            if let Some(fix) = &issue.fix {
+                apply_fix(&vault, &issue.path, fix).unwrap();
+            }
+        }
+        assert!(apply_fix(&vault, "plain.md", &DoctorFix::AddFrontmatter).is_err());
+        assert_eq!(fs::read_to_string(vault.join("legacy.md")).unwrap(), "caf\u{e9}");
+
+        // The converted file surfaces its next problem on the following run
+        let report = diagnose_vault(&vault).unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!((report.issues[0].path.as_str(), report.issues[0].fix.clone()), ("legacy.md", Some(DoctorFix::AddFrontmatter)));
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Migration: unmanaged markdown gains frontmatter, its body stays byte-for-byte
+    #[test]
+    fn test_migration_adds_managed_fields() {
+        let vault = temp_vault();
+        let body = "# Imported\r\n\r\nKept as-is [[Link]]\n";
+        fs::write(vault.join("Imported.md"), body).unwrap();
+        fs::write(vault.join("Managed.md"), render_note(&new_metadata("Managed", Vec::new()), "Body").unwrap()).unwrap();
+
+        let preview = run_migrations(&vault, true).unwrap();
+        assert_eq!((preview.from_version, preview.migrated.len(), preview.unchanged), (0, 1, 1));
+        assert_eq!(preview.migrated[0].added, vec!["id", "title", "created_at", "updated_at", "version", "device_id"]);
+        assert_eq!(fs::read_to_string(vault.join("Imported.md")).unwrap(), body);
+
+        run_migrations(&vault, false).unwrap();
+        let raw = fs::read_to_string(vault.join("Imported.md")).unwrap();
+        let document = Document::parse(&raw).unwrap();
+        let metadata = document.metadata().unwrap();
+        assert_eq!((metadata.title.as_str(), metadata.version), ("Imported", 1));
+        assert_eq!(metadata.updated_at, metadata.created_at);
+        assert!(raw.ends_with(body));
+        assert_eq!(load_config(&vault).unwrap().schema_version, SCHEMA_VERSION);
+
+        let again = run_migrations(&vault, false).unwrap();
+        assert_eq!((again.migrated.len(), again.unchanged), (0, 2));
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Graph cache: only changed files are parsed again
+    #[test]
+    fn test_graph_rebuild_is_incremental() {
+        let vault = temp_vault();
+        let alpha = new_metadata("Alpha", Vec::new());
+        fs::write(vault.join("Alpha.md"), render_note(&alpha, "[[Beta]]").unwrap()).unwrap();
+        fs::write(vault.join("Beta.md"), "plain").unwrap();
+
+        let mut cache = GraphCache::default();
+        assert_eq!(refresh_cache(&vault, &mut cache).unwrap(), RebuildStats { parsed: 2, reused: 0, updated: vec!["Alpha.md".to_string(), "Beta.md".to_string()], removed: vec![] });
+        assert_eq!(refresh_cache(&vault, &mut cache).unwrap(), RebuildStats { parsed: 0, reused: 2, updated: vec![], removed: vec![] });
+
+        fs::write(vault.join("Alpha.md"), render_note(&alpha, "[[Gamma]]").unwrap()).unwrap();
+        fs::remove_file(vault.join("Beta.md")).unwrap();
+        assert_eq!(refresh_cache(&vault, &mut cache).unwrap(), RebuildStats { parsed: 1, reused: 0, updated: vec!["Alpha.md".to_string()], removed: vec!["Beta.md".to_string()] });
+
+        let graph = assemble_graph(&cache);
+        assert_eq!(graph.links.len(), 1);
+        assert_eq!((graph.links[0].source.as_str(), graph.links[0].target.as_str()), (alpha.id.as_str(), "Gamma"));
+        let ghost = graph.nodes.iter().find(|node| node.id == "Gamma").unwrap();
+        assert_eq!(ghost.kind, NodeKind::Ghost);
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Graph index: survives a restart and answers backlink and tag queries
+    #[tokio::test]
+    async fn test_graph_store_round_trip() {
+        let vault = temp_vault();
+        let target = new_metadata("Target", Vec::new());
+        let source = new_metadata("Source", vec!["#Work".to_string()]);
+        fs::write(vault.join("Target.md"), render_note(&target, "Body").unwrap()).unwrap();
+        fs::write(vault.join("Source.md"), render_note(&source, "See [[target#Intro|it]]").unwrap()).unwrap();
+        let db_path = vault.join(".secretariat/graph.db");
+
+        let mut cache = GraphCache::default();
+        let stats = refresh_cache(&vault, &mut cache).unwrap();
+        let updated: Vec<_> = stats.updated.iter().map(|path| (path.clone(), cache.files[path].clone())).collect();
+        let store = GraphStore::open(&db_path, &vault).await.unwrap();
+        store.save(&updated, &stats.removed).await.unwrap();
+        drop(store);
+
+        // A restart reopens the same database; nothing has to be parsed again
+        let store = GraphStore::open(&db_path, &vault).await.unwrap();
+        let mut restored = GraphCache { vault_path: vault.clone(), files: store.load().await.unwrap() };
+        assert_eq!(restored.files["Source.md"].links[0].raw, "target#Intro|it");
+        let summary = restored.files["Source.md"].summary("Source.md");
+        assert_eq!((summary.id, summary.created_at, summary.word_count), (Some(source.id.clone()), Some(source.created_at.clone()), 2));
+        assert_eq!(refresh_cache(&vault, &mut restored).unwrap().parsed, 0);
+
+        let source_note = IndexedNote { id: source.id.clone(), title: "Source".to_string(), path: "Source.md".to_string() };
+        assert_eq!(store.backlinks(&target.id).await.unwrap(), vec![source_note.clone()]);
+        assert_eq!(store.notes_with_tag("#work").await.unwrap(), vec![source_note.clone()]);
+        assert_eq!(store.notes_with_tag("work").await.unwrap(), vec![source_note]);
+
+        store.save(&[], &["Source.md".to_string()]).await.unwrap();
+        assert!(store.backlinks(&target.id).await.unwrap().is_empty());
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Backlinks: a name two notes share belongs to the one the graph links to
+    #[tokio::test]
+    async fn test_backlinks_follow_link_precedence() {
+        let vault = temp_vault();
+        let titled = new_metadata("Plan", Vec::new());
+        let named = new_metadata("Roadmap", Vec::new());
+        fs::write(vault.join("A.md"), render_note(&titled, "Body").unwrap()).unwrap();
+        fs::write(vault.join("Plan.md"), render_note(&named, "Body").unwrap()).unwrap();
+        fs::write(vault.join("Src.md"), "See [[Plan]]").unwrap();
+
+        let mut cache = GraphCache::default();
+        let stats = refresh_cache(&vault, &mut cache).unwrap();
+        let updated: Vec<_> = stats.updated.iter().map(|path| (path.clone(), cache.files[path].clone())).collect();
+        let store = GraphStore::open(&vault.join(".secretariat/graph.db"), &vault).await.unwrap();
+        store.save(&updated, &[]).await.unwrap();
+
+        let graph = assemble_graph(&cache);
+        assert_eq!(graph.links.iter().map(|link| link.target.as_str()).collect::<Vec<_>>(), vec![named.id.as_str()]);
+        assert_eq!(store.backlinks(&named.id).await.unwrap().len(), 1);
+        assert!(store.backlinks(&titled.id).await.unwrap().is_empty());
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Wikilink grammar: target, heading, block and alias are separate parts
+    #[test]
+    fn test_wikilink_parsing() {
+        let link = WikiLink::parse("Note B#Budget|see here");
+        assert_eq!((link.target.as_str(), link.heading.as_deref(), link.alias.as_deref()), ("Note B", Some("Budget"), Some("see here")));
+        let link = WikiLink::parse("Note B#^abc123");
+        assert_eq!((link.block.as_deref(), link.heading), (Some("abc123"), None));
+        let link = WikiLink::parse("#Intro");
+        assert_eq!((link.target.as_str(), link.heading.as_deref()), ("", Some("Intro")));
+        assert_eq!(WikiLink::parse("Note B\\|table alias").target, "Note B");
+
+        assert_eq!(normalize_name("Café  Notes.md"), normalize_name("cafe notes"));
+    }
+
+    // Link resolution: aliases, headings and accents all reach the real note
+    #[test]
+    fn test_graph_resolves_aliases_and_accents() {
+        let vault = temp_vault();
+        let mut budget = new_metadata("Café Budget", Vec::new());
+        budget.extra.insert("aliases".into(), serde_yaml::Value::from(vec!["Money"]));
+        fs::write(vault.join("Café Budget.md"), render_note(&budget, "Numbers").unwrap()).unwrap();
+        let links = "[[cafe budget#Q1|the budget]] [[Money]] [[CAFÉ BUDGET#^row1]] [[Idea]] [[idea]]";
+        fs::write(vault.join("Plan.md"), render_note(&new_metadata("Plan", Vec::new()), links).unwrap()).unwrap();
+
+        let mut cache = GraphCache::default();
+        refresh_cache(&vault, &mut cache).unwrap();
+        let graph = assemble_graph(&cache);
+        let targets: HashSet<&str> = graph.links.iter().map(|link| link.target.as_str()).collect();
+        assert_eq!(targets, HashSet::from([budget.id.as_str(), "Idea"]));
+        assert_eq!(graph.nodes.iter().filter(|node| node.kind == NodeKind::Ghost).count(), 1);
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Dotted note names: `[[v1.2 Release]]` is a note, not a `.2 Release` file
+    #[test]
+    fn test_dotted_note_names_are_not_attachments() {
+        let vault = temp_vault();
+        let release = new_metadata("v1.2 Release", Vec::new());
+        fs::write(vault.join("v1.2 Release.md"), render_note(&release, "Notes").unwrap()).unwrap();
+        fs::write(vault.join("Plan.md"), render_note(&new_metadata("Plan", Vec::new()), "See [[v1.2 Release]]").unwrap()).unwrap();
+
+        let mut cache = GraphCache::default();
+        refresh_cache(&vault, &mut cache).unwrap();
+        let graph = assemble_graph(&cache);
+        assert_eq!(graph.links.len(), 1);
+        assert_eq!((graph.links[0].target.as_str(), graph.links[0].kind), (release.id.as_str(), LinkKind::Link));
+        assert!(diagnose_vault(&vault).unwrap().issues.iter().all(|issue| issue.kind != IssueKind::BrokenLink));
+        assert!(!is_attachment_target("Meeting 2024.05.01") && !is_attachment_target("Dr. Smith"));
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Link extraction: code is not scanned, markdown links and embeds are typed edges
+    #[test]
+    fn test_markdown_aware_link_extraction() {
+        let content = "Run `[[ -f x ]]` first.\n\n```bash\nif [[ -d \"$dir\" ]]; then echo ok; fi\n```\n\n\
+            See [[Real]], ![[Diagram]], [plan](../Other%20Note.md#Goals), [site](https://example.com) and ![](scan.png).\n";
+        let links = parse_note_links(content);
+        let raws: Vec<&str> = links.iter().map(|link| link.raw.as_str()).collect();
+        assert_eq!(raws, vec!["Real", "Diagram", "../Other Note.md#Goals", "scan.png"]);
+        assert_eq!((links[1].embed, links[2].syntax), (true, LinkSyntax::Markdown));
+        assert_eq!(links[2].resolve_from("Projects/Plan.md").target, "Other Note.md");
+
+        let vault = temp_vault();
+        fs::create_dir(vault.join("Projects")).unwrap();
+        let other = new_metadata("Other Note", Vec::new());
+        fs::write(vault.join("Other Note.md"), render_note(&other, "Goals").unwrap()).unwrap();
+        fs::write(vault.join("Diagram.md"), "plain").unwrap();
+        fs::write(vault.join("Projects/Plan.md"), content).unwrap();
+
+        let mut cache = GraphCache::default();
+        refresh_cache(&vault, &mut cache).unwrap();
+        let graph = assemble_graph(&cache);
+        let kind_of = |target: &str| graph.links.iter().find(|link| link.target == target).map(|link| link.kind);
+        assert_eq!(kind_of(&other.id), Some(LinkKind::Markdown));
+        assert_eq!(kind_of("Diagram"), Some(LinkKind::Embed));
+        assert_eq!(kind_of("scan.png"), Some(LinkKind::Attachment));
+        assert_eq!(kind_of("Real"), Some(LinkKind::Link));
+        assert_eq!(graph.links.len(), 4);
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
+
+    // Centrality: degrees, PageRank and betweenness on the directed link graph
+    #[test]
+    fn test_graph_centrality_metrics() {
+        let vault = temp_vault();
+        // A -> B -> C and D -> B, with a duplicate and a self-link that must not count
+        for (name, body) in [("A", "[[B]] [[B|again]] [[A]]"), ("B", "[[C]]"), ("C", ""), ("D", "[[B]]")] {
+            fs::write(vault.join(format!("{}.md", name)), body).unwrap();
+        }
+
+        let mut cache = GraphCache::default();
+        refresh_cache(&vault, &mut cache).unwrap();
+        let graph = assemble_graph(&cache);
+        let node = |id: &str| graph.nodes.iter().find(|node| node.id == id).unwrap().metrics;
+
+        assert_eq!((node("B").in_degree, node("B").out_degree), (2, 1));
+        assert_eq!((node("A").in_degree, node("A").out_degree), (0, 1));
+        assert!((node("B").betweenness - 2.0 / 6.0).abs() < 1e-9); // On A->C and D->C, of 6 ordered pairs
+        assert_eq!(node("A").betweenness, 0.0);
+        let total: f64 = graph.nodes.iter().map(|node| node.metrics.page_rank).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(node("C").page_rank > node("B").page_rank && node("B").page_rank > node("A").page_rank);
+
+        let top: Vec<String> = rank_notes(graph.nodes.clone(), Metric::InDegree, 2).into_iter().map(|node| node.id).collect();
+        assert_eq!(top[0], "B");
+        assert_eq!(top.len(), 2);
+
+        fs::remove_dir_all(&vault).unwrap();
+    }
 }
@@ -0,0 +1,206 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use chrono::{DateTime, Duration, Utc};
+use crate::device_identity::current_device_id;
+use crate::frontmatter::Document;
+use crate::vault_manager::{parse_note, save_json, save_note, touch_metadata, VaultPath, VaultState};
+
+/// Deleted files live here until purged. Hidden, so vault scans never see them.
+pub const TRASH_DIR: &str = ".trash";
+const TRASH_INDEX: &str = ".trash/index.json";
+/// Note ids deleted on this device, kept after purge so sync never resurrects them.
+const TOMBSTONES_FILE: &str = ".secretariat/tombstones.json";
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashEntry {
+    pub entry_id: String,
+    pub original_path: String,
+    pub note_id: Option<String>,
+    pub title: Option<String>,
+    pub deleted_at: String, // ISO 8601
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Tombstone {
+    pub id: String,
+    pub version: u64,
+    pub deleted_at: String,
+    #[serde(default)] // Device that deleted the note
+    pub device_id: String,
+}
+
+#[tauri::command]
+pub fn delete_note(state: State<'_, VaultState>, filename: String) -> Result<TrashEntry, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let note_path = VaultPath::new(&vault_path, &filename)?;
+    move_to_trash(&vault_path, &note_path)
+}
+
+#[tauri::command]
+pub fn list_trash(state: State<'_, VaultState>) -> Result<Vec<TrashEntry>, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let mut entries = load_trash_index(&vault_path)?;
+    entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(entries)
+}
+
+/// Moves a trashed file back to where it was deleted from. Returns its path.
+#[tauri::command]
+pub fn restore_note(state: State<'_, VaultState>, entry_id: String) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    restore_from_trash(&vault_path, &entry_id)
+}
+
+pub fn restore_from_trash(vault_path: &Path, entry_id: &str) -> Result<String, String> {
+    let mut entries = load_trash_index(vault_path)?;
+    let position = entries.iter().position(|e| e.entry_id == entry_id).ok_or("Trash entry not found")?;
+    let entry = entries[position].clone();
+
+    let trashed_path = VaultPath::new(vault_path, &trashed_file(&entry))?;
+    let original_path = VaultPath::new(vault_path, &entry.original_path)?;
+    if original_path.as_path().exists() {
+        return Err(format!("A file already exists at {}", entry.original_path));
+    }
+    if let Some(parent) = original_path.as_path().parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut tombstones = load_tombstones(vault_path)?;
+    let tombstone = entry.note_id.as_ref().and_then(|id| tombstones.remove(id));
+
+    let raw = fs::read(&trashed_path).map_err(|e| e.to_string())?;
+    let document = String::from_utf8(raw.clone()).ok().and_then(|s| Document::parse(&s).ok());
+    let restored = match (document, &tombstone) {
+        // The tombstone may already be on the remote index: the restored note has to
+        // outrank it, or the next sync would delete it again.
+        (Some(mut document), Some(tombstone)) => {
+            let mut metadata = document.metadata()?;
+            metadata.version = metadata.version.max(tombstone.version);
+            touch_metadata(&mut metadata);
+            document.set_metadata(&metadata)?;
+            document.render()?.into_bytes()
+        },
+        _ => raw,
+    };
+
+    save_note(vault_path, &original_path, restored)?;
+    remove_trashed_entry(vault_path, &entry)?;
+    entries.remove(position);
+    save_json(&vault_path.join(TRASH_INDEX), &entries)?;
+    save_json(&vault_path.join(TOMBSTONES_FILE), &tombstones)?;
+
+    Ok(entry.original_path)
+}
+
+#[derive(Serialize, Debug)]
+pub struct PurgeReport {
+    pub purged: usize,
+    pub unreadable: Vec<String>, // Entries kept because their `deleted_at` does not parse
+}
+
+/// Permanently removes trash entries older than `retention_days` (30 by default).
+/// Tombstones are kept: other devices may still need to learn about the deletion.
+#[tauri::command]
+pub fn purge_trash(state: State<'_, VaultState>, retention_days: Option<i64>) -> Result<PurgeReport, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    purge_expired(&vault_path, retention_days.unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+pub fn purge_expired(vault_path: &Path, retention_days: i64) -> Result<PurgeReport, String> {
+    let cutoff = Utc::now() - Duration::days(retention_days);
+
+    // An entry of unknown age is never deleted for good
+    let mut unreadable = Vec::new();
+    let (expired, kept): (Vec<TrashEntry>, Vec<TrashEntry>) = load_trash_index(vault_path)?
+        .into_iter()
+        .partition(|entry| match DateTime::parse_from_rfc3339(&entry.deleted_at) {
+            Ok(deleted_at) => deleted_at <= cutoff,
+            Err(_) => {
+                unreadable.push(entry.original_path.clone());
+                false
+            },
+        });
+    if !unreadable.is_empty() {
+        println!("Kept {} trash entries with an unreadable deletion date", unreadable.len());
+    }
+
+    for entry in &expired {
+        remove_trashed_entry(vault_path, entry)?;
+    }
+    save_json(&vault_path.join(TRASH_INDEX), &kept)?;
+
+    Ok(PurgeReport { purged: expired.len(), unreadable })
+}
+
+/// Soft-deletes a file: it is moved under `.trash/` and, for managed notes,
+/// a tombstone is left for `SyncEngine`.
+pub fn move_to_trash(vault_path: &Path, path: &VaultPath) -> Result<TrashEntry, String> {
+    let file_name = path.as_path().file_name().and_then(|n| n.to_str()).ok_or("Invalid file path")?;
+    let metadata = fs::read_to_string(path).ok().and_then(|raw| parse_note(&raw).ok()).map(|(metadata, _)| metadata);
+
+    let entry = TrashEntry {
+        entry_id: uuid::Uuid::new_v4().to_string(),
+        original_path: path.relative().to_string_lossy().to_string(),
+        note_id: metadata.as_ref().map(|m| m.id.clone()),
+        title: metadata.as_ref().map(|m| m.title.clone()),
+        deleted_at: Utc::now().to_rfc3339(),
+    };
+
+    let entry_dir = vault_path.join(TRASH_DIR).join(&entry.entry_id);
+    fs::create_dir_all(&entry_dir).map_err(|e| e.to_string())?;
+    fs::rename(path, entry_dir.join(file_name)).map_err(|e| e.to_string())?;
+
+    let mut entries = load_trash_index(vault_path)?;
+    entries.push(entry.clone());
+    save_json(&vault_path.join(TRASH_INDEX), &entries)?;
+
+    if let Some(metadata) = metadata {
+        // One past the last live version, so the deletion wins over it on every device
+        record_tombstone(vault_path, &metadata.id, metadata.version + 1, &current_device_id())?;
+    }
+
+    Ok(entry)
+}
+
+pub fn record_tombstone(vault_path: &Path, id: &str, version: u64, device_id: &str) -> Result<(), String> {
+    let mut tombstones = load_tombstones(vault_path)?;
+    tombstones.insert(id.to_string(), Tombstone {
+        id: id.to_string(),
+        version,
+        deleted_at: Utc::now().to_rfc3339(),
+        device_id: device_id.to_string(),
+    });
+
+    save_json(&vault_path.join(TOMBSTONES_FILE), &tombstones)
+}
+
+pub fn load_tombstones(vault_path: &Path) -> Result<HashMap<String, Tombstone>, String> {
+    match fs::read_to_string(vault_path.join(TOMBSTONES_FILE)) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        Err(_) => Ok(HashMap::new()),
+    }
+}
+
+fn load_trash_index(vault_path: &Path) -> Result<Vec<TrashEntry>, String> {
+    match fs::read_to_string(vault_path.join(TRASH_INDEX)) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn trashed_file(entry: &TrashEntry) -> String {
+    let file_name = Path::new(&entry.original_path).file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    format!("{}/{}/{}", TRASH_DIR, entry.entry_id, file_name)
+}
+
+fn remove_trashed_entry(vault_path: &Path, entry: &TrashEntry) -> Result<(), String> {
+    let entry_dir = VaultPath::new(vault_path, &format!("{}/{}", TRASH_DIR, entry.entry_id))?;
+    match fs::remove_dir_all(&entry_dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+        _ => Ok(()),
+    }
+}
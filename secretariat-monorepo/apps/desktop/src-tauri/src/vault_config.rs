@@ -0,0 +1,84 @@
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use crate::vault_manager::{save_json, VaultState};
+
+/// Per-vault settings, stored inside the vault so they travel with it.
+const CONFIG_FILE: &str = ".secretariat/config.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)] // Older config files miss newer sections
+pub struct VaultConfig {
+    pub daily_notes: DailyNotesConfig,
+    pub history: HistoryConfig,
+    pub files: FilesConfig,
+    pub schema_version: u32, // Note schema every file was last migrated to; 0 before migrations existed
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DailyNotesConfig {
+    pub folder: String,
+    pub date_format: String, // chrono strftime syntax
+    pub template: Option<String>, // Vault-relative path of the template note
+    pub default_heading: String,
+}
+
+impl Default for DailyNotesConfig {
+    fn default() -> Self {
+        Self {
+            folder: "Daily".to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            template: None,
+            default_heading: "Log".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub max_revisions: usize, // Per note; the oldest snapshots are dropped first
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self { max_revisions: 50 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct FilesConfig {
+    pub collision_suffix: String, // `{n}` is replaced by 1, 2, ... until the name is free
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        Self { collision_suffix: " ({n})".to_string() }
+    }
+}
+
+pub fn load_config(vault_path: &Path) -> Result<VaultConfig, String> {
+    match fs::read_to_string(vault_path.join(CONFIG_FILE)) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        Err(_) => Ok(VaultConfig::default()),
+    }
+}
+
+pub fn save_config(vault_path: &Path, config: &VaultConfig) -> Result<(), String> {
+    save_json(&vault_path.join(CONFIG_FILE), config)
+}
+
+#[tauri::command]
+pub fn get_vault_config(state: State<'_, VaultState>) -> Result<VaultConfig, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    load_config(&vault_path)
+}
+
+#[tauri::command]
+pub fn update_vault_config(state: State<'_, VaultState>, config: VaultConfig) -> Result<(), String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    save_config(&vault_path, &config)
+}
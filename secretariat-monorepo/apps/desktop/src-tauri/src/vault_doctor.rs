@@ -0,0 +1,219 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use serde_yaml::Value;
+use crate::attachment_manager::find_unreferenced_attachments;
+use crate::filename_policy::unique_note_path;
+use crate::frontmatter::Document;
+use crate::graph_engine::is_attachment_target;
+use crate::migration_manager::migrate_note;
+use crate::trash_manager::move_to_trash;
+use crate::vault_manager::{
+    is_hidden, list_markdown_files, new_metadata, render_note, save_file, save_note, touch_metadata, VaultPath, VaultState,
+};
+use crate::wikilink::{frontmatter_aliases, parse_note_links, LinkResolver, NoteLink};
+
+/// Fields every managed note needs for listing, history and sync.
+const REQUIRED_FIELDS: [&str; 5] = ["id", "title", "created_at", "updated_at", "version"];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    MissingFrontmatter,
+    MalformedFrontmatter,
+    IncompleteFrontmatter,
+    DuplicateId,
+    BrokenLink,
+    OrphanAttachment,
+    NonUtf8,
+}
+
+/// Repairs `vault_doctor` can offer. Each one only touches the file it is reported for.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DoctorFix {
+    AddFrontmatter,
+    FillMissingFields,
+    AssignNewId,
+    CreateMissingNote { target: String },
+    TrashAttachment,
+    ConvertToUtf8, // Reads the file as Latin-1; the original goes to the trash
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DoctorIssue {
+    pub kind: IssueKind,
+    pub path: String,
+    pub message: String,
+    pub fix: Option<DoctorFix>, // None when only the user can decide
+}
+
+#[derive(Serialize, Debug)]
+pub struct DoctorReport {
+    pub files_checked: usize,
+    pub issues: Vec<DoctorIssue>,
+}
+
+/// Checks every note in the vault without changing anything.
+#[tauri::command]
+pub fn vault_doctor(state: State<'_, VaultState>) -> Result<DoctorReport, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    diagnose_vault(&vault_path)
+}
+
+/// Applies one fix from a `vault_doctor` report.
+#[tauri::command]
+pub fn apply_doctor_fix(state: State<'_, VaultState>, path: String, fix: DoctorFix) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    apply_fix(&vault_path, &path, &fix)
+}
+
+pub fn diagnose_vault(vault_path: &Path) -> Result<DoctorReport, String> {
+    let mut issues = Vec::new();
+    let issue = |kind, path: &str, message: String, fix| DoctorIssue { kind, path: path.to_string(), message, fix };
+
+    let files = list_markdown_files(vault_path)?;
+    let mut ids: HashMap<String, Vec<String>> = HashMap::new();
+    let mut resolver = LinkResolver::default(); // Only existence matters, so notes are keyed by path
+    let mut links: Vec<(String, NoteLink)> = Vec::new(); // (Source path, link)
+
+    for path in &files {
+        let relative = path.relative().to_string_lossy().to_string();
+        resolver.add_note(&relative, &relative, None, &[]);
+
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        let Ok(raw) = String::from_utf8(bytes) else {
+            issues.push(issue(IssueKind::NonUtf8, &relative, "File is not valid UTF-8".to_string(), Some(DoctorFix::ConvertToUtf8)));
+            continue;
+        };
+        links.extend(parse_note_links(&raw).into_iter().map(|link| (relative.clone(), link)));
+
+        let document = match Document::parse(&raw) {
+            Ok(document) => document,
+            Err(e) => {
+                issues.push(issue(IssueKind::MalformedFrontmatter, &relative, format!("Invalid YAML: {}", e), None));
+                continue;
+            },
+        };
+        if !document.has_frontmatter() {
+            issues.push(issue(IssueKind::MissingFrontmatter, &relative, "No frontmatter".to_string(), Some(DoctorFix::AddFrontmatter)));
+            continue;
+        }
+
+        let missing: Vec<&str> = REQUIRED_FIELDS.iter().copied().filter(|key| document.get(key).is_none()).collect();
+        if !missing.is_empty() {
+            let message = format!("Missing {}", missing.join(", "));
+            issues.push(issue(IssueKind::IncompleteFrontmatter, &relative, message, Some(DoctorFix::FillMissingFields)));
+        }
+        let title = document.get("title").and_then(Value::as_str);
+        resolver.add_note(&relative, &relative, title, &frontmatter_aliases(&document));
+        match document.get("id") {
+            Some(Value::String(id)) => ids.entry(id.clone()).or_default().push(relative.clone()),
+            Some(_) => issues.push(issue(IssueKind::MalformedFrontmatter, &relative, "id is not a string".to_string(), None)),
+            None => {},
+        }
+        if missing.is_empty() {
+            if let Err(e) = document.metadata() {
+                issues.push(issue(IssueKind::MalformedFrontmatter, &relative, e, None));
+            }
+        }
+    }
+
+    // The first file keeps a shared id; copies get a new one
+    let mut duplicates: Vec<(String, Vec<String>)> = ids.into_iter().filter(|(_, paths)| paths.len() > 1).collect();
+    duplicates.sort();
+    for (id, paths) in duplicates {
+        for path in &paths[1..] {
+            let message = format!("Id {} is also used by {}", id, paths[0]);
+            issues.push(issue(IssueKind::DuplicateId, path, message, Some(DoctorFix::AssignNewId)));
+        }
+    }
+
+    let attachment_names = attachment_names(vault_path)?;
+    let mut reported: HashSet<(String, String)> = HashSet::new();
+    for (source, link) in links {
+        let target = link.resolve_from(&source).target;
+        if target.is_empty() {
+            continue;
+        }
+        let file_name = Path::new(&target).file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let note = resolver.resolve(&target).is_some();
+        let attachment = !note && is_attachment_target(&target);
+        let exists = note || (attachment && attachment_names.contains(&file_name));
+        if !exists && reported.insert((source.clone(), target.clone())) {
+            // A note is only created for a plain name; a path would need its folders too
+            let creatable = !attachment && !target.contains('/');
+            let fix = creatable.then(|| DoctorFix::CreateMissingNote { target: target.clone() });
+            issues.push(issue(IssueKind::BrokenLink, &source, format!("[[{}]] points to nothing", target), fix));
+        }
+    }
+
+    for path in find_unreferenced_attachments(vault_path)? {
+        issues.push(issue(IssueKind::OrphanAttachment, &path, "No note links to this file".to_string(), Some(DoctorFix::TrashAttachment)));
+    }
+
+    Ok(DoctorReport { files_checked: files.len(), issues })
+}
+
+pub fn apply_fix(vault_path: &Path, relative: &str, fix: &DoctorFix) -> Result<String, String> {
+    let path = VaultPath::new(vault_path, relative)?;
+
+    match fix {
+        DoctorFix::AddFrontmatter | DoctorFix::FillMissingFields => {
+            let added = migrate_note(vault_path, &path, false)?;
+            if added.is_empty() {
+                return Err(format!("{} already has every managed field", relative));
+            }
+            Ok(format!("Added {} to {}", added.join(", "), relative))
+        },
+        DoctorFix::AssignNewId => {
+            let mut document = Document::parse(&fs::read_to_string(&path).map_err(|e| e.to_string())?)?;
+            let mut metadata = document.metadata()?;
+            metadata.id = uuid::Uuid::new_v4().to_string();
+            touch_metadata(&mut metadata);
+            document.set_metadata(&metadata)?;
+            save_note(vault_path, &path, document.render()?)?;
+            Ok(format!("{} now has id {}", relative, metadata.id))
+        },
+        DoctorFix::CreateMissingNote { target } => {
+            let note_path = unique_note_path(vault_path, "", target, None)?;
+            let metadata = new_metadata(target, Vec::new());
+            save_note(vault_path, &note_path, render_note(&metadata, &format!("# {}\n", target))?)?;
+            Ok(format!("Created {}", note_path.relative().display()))
+        },
+        DoctorFix::TrashAttachment => {
+            move_to_trash(vault_path, &path)?;
+            Ok(format!("Moved {} to the trash", relative))
+        },
+        DoctorFix::ConvertToUtf8 => {
+            let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+            if String::from_utf8(bytes.clone()).is_ok() {
+                return Err(format!("{} is already UTF-8", relative));
+            }
+            // Latin-1 maps every byte to a character, so nothing is dropped
+            let converted: String = bytes.iter().map(|&b| b as char).collect();
+            move_to_trash(vault_path, &path)?;
+            save_file(path.as_path(), converted)?;
+            Ok(format!("Converted {} to UTF-8; the original is in the trash", relative))
+        },
+    }
+}
+
+/// Lower-cased names of every non-note file, which `![[...]]` embeds may point at.
+fn attachment_names(vault_path: &Path) -> Result<HashSet<String>, String> {
+    let pattern = vault_path.join("**/*");
+    let pattern_str = pattern.to_str().ok_or("Invalid vault path")?;
+
+    let mut names = HashSet::new();
+    for path in glob::glob(pattern_str).map_err(|e| e.to_string())?.flatten() {
+        let Ok(relative) = path.strip_prefix(vault_path) else { continue };
+        if path.is_file() && !is_hidden(relative) {
+            if let Some(name) = path.file_name() {
+                names.insert(name.to_string_lossy().to_lowercase());
+            }
+        }
+    }
+    Ok(names)
+}
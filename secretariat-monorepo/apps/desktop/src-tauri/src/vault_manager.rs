@@ -1,10 +1,23 @@
 use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use tauri::State;
-use chrono::Utc;
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
+use chrono::{Local, NaiveDate, Utc};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use crate::graph_engine::{rewrite_markdown_links, rewrite_wikilinks, sync_index, GraphState};
+use crate::vault_config::load_config;
+use crate::template_manager::{apply_presets, instantiate_template, TEMPLATES_DIR};
+use crate::history_manager::snapshot_revision;
+use crate::frontmatter::{Document};
+use crate::device_identity::current_device_id;
+use crate::filename_policy::{sanitize_filename, unique_note_path};
+use crate::wikilink::{frontmatter_aliases, LinkResolver};
+use serde_yaml::Mapping;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Note {
     pub id: String,
     pub title: String,
@@ -20,9 +33,222 @@ pub struct Note {
     pub tags: Vec<String>
 }
 
+impl Note {
+    pub fn new(metadata: NoteMetadata, content: String) -> Self {
+        Self {
+            id: metadata.id,
+            title: metadata.title,
+            content,
+            created_at: metadata.created_at,
+            updated_at: metadata.updated_at,
+            version: metadata.version,
+            device_id: metadata.device_id,
+            tags: metadata.tags,
+        }
+    }
+}
+
 // TODO: Use a proper AppState to store the Vault Path
 pub struct VaultState {
     pub vault_path: std::sync::Mutex<PathBuf>,
+    pub note_index: std::sync::Mutex<NoteIndex>,
+}
+
+impl VaultState {
+    pub fn new(vault_path: PathBuf) -> Self {
+        Self {
+            vault_path: std::sync::Mutex::new(vault_path),
+            note_index: std::sync::Mutex::new(NoteIndex::default()),
+        }
+    }
+
+    /// Path of the note with this frontmatter `id` in the active vault.
+    pub fn find_note(&self, id: &str) -> Result<(PathBuf, VaultPath), String> {
+        let vault_path = self.vault_path.lock().map_err(|e| e.to_string())?.clone();
+        let note_path = self.note_index.lock().map_err(|e| e.to_string())?.resolve(&vault_path, id)?;
+        Ok((vault_path, note_path))
+    }
+}
+
+/// Note id -> vault-relative path. Kept current by the watcher; every hit is
+/// checked against the file, and a miss rescans the vault, so a stale entry is
+/// never returned.
+#[derive(Debug, Default)]
+pub struct NoteIndex {
+    vault_path: PathBuf,
+    paths: HashMap<String, String>,
+}
+
+impl NoteIndex {
+    pub fn resolve(&mut self, vault_path: &Path, id: &str) -> Result<VaultPath, String> {
+        if self.vault_path != vault_path {
+            self.vault_path = vault_path.to_path_buf();
+            self.paths.clear();
+        }
+        if let Some(relative) = self.paths.get(id) {
+            if let Ok(note_path) = VaultPath::new(vault_path, relative) {
+                if read_note_id(&note_path).as_deref() == Some(id) {
+                    return Ok(note_path);
+                }
+            }
+        }
+
+        self.rebuild(vault_path)?;
+        let relative = self.paths.get(id).ok_or_else(|| format!("Note not found: {}", id))?;
+        VaultPath::new(vault_path, relative)
+    }
+
+    pub fn rebuild(&mut self, vault_path: &Path) -> Result<(), String> {
+        self.vault_path = vault_path.to_path_buf();
+        self.paths.clear();
+        for path in list_markdown_files(vault_path)? {
+            if let Some(id) = read_note_id(&path) {
+                let relative = path.relative().to_string_lossy().to_string();
+                // Copied files share an id; the first one found keeps it
+                if let Some(existing) = self.paths.get(&id) {
+                    println!("Duplicate note id {} in {} and {}", id, existing, relative);
+                    continue;
+                }
+                self.paths.insert(id, relative);
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-reads one file after it was created, changed or moved.
+    pub fn update(&mut self, vault_path: &Path, relative: &str) {
+        if self.vault_path != vault_path {
+            return; // Not built for this vault yet; the next lookup rebuilds it
+        }
+        self.remove(relative);
+        let id = VaultPath::new(vault_path, relative).ok().and_then(|path| read_note_id(&path));
+        if let Some(id) = id {
+            self.paths.entry(id).or_insert_with(|| relative.to_string());
+        }
+    }
+
+    pub fn remove(&mut self, relative: &str) {
+        self.paths.retain(|_, path| path != relative);
+    }
+}
+
+fn read_note_id(path: &VaultPath) -> Option<String> {
+    let raw = fs::read_to_string(path).ok()?;
+    Some(Document::parse(&raw).ok()?.metadata().ok()?.id)
+}
+
+/// Suffix of in-flight writes. Anything still carrying it at startup is debris from a crash.
+const TEMP_SUFFIX: &str = ".secretariat-tmp";
+
+/// A path proven to stay inside the vault.
+/// Strings coming from the webview are untrusted: every command resolves them through
+/// `VaultPath` instead of joining them onto the vault root itself.
+#[derive(Debug, Clone)]
+pub struct VaultPath {
+    full_path: PathBuf,
+    relative: PathBuf,
+}
+
+impl VaultPath {
+    /// Resolves a vault-relative path. Absolute paths, `..` and symlinks that
+    /// lead outside of the vault are rejected.
+    pub fn new(vault_root: &Path, relative: &str) -> Result<Self, String> {
+        let mut clean = PathBuf::new();
+        for component in Path::new(relative).components() {
+            match component {
+                Component::Normal(part) => clean.push(part),
+                Component::CurDir => {},
+                Component::ParentDir => return Err(format!("Path escapes the vault: {}", relative)),
+                Component::RootDir | Component::Prefix(_) => {
+                    return Err(format!("Absolute paths are not allowed: {}", relative))
+                }
+            }
+        }
+        if clean.as_os_str().is_empty() {
+            return Err("Empty path".to_string());
+        }
+
+        let root = fs::canonicalize(vault_root).map_err(|e| e.to_string())?;
+        let full_path = root.join(&clean);
+
+        // The deepest entry that already exists decides where the path really points:
+        // a symlinked file or folder may lead anywhere on disk.
+        let existing = full_path
+            .ancestors()
+            .find(|p| fs::symlink_metadata(p).is_ok())
+            .ok_or("Vault root does not exist")?;
+        let resolved = fs::canonicalize(existing)
+            .map_err(|_| format!("Broken link in path: {}", relative))?;
+        if !resolved.starts_with(&root) {
+            return Err(format!("Path escapes the vault: {}", relative));
+        }
+
+        Ok(Self { full_path, relative: clean })
+    }
+
+    /// Wraps a path found while scanning the vault (e.g. a glob result).
+    pub fn from_full_path(vault_root: &Path, full_path: &Path) -> Result<Self, String> {
+        let relative = full_path
+            .strip_prefix(vault_root)
+            .map_err(|_| format!("Path is outside the vault: {}", full_path.display()))?;
+        let relative = relative.to_str().ok_or("Path is not valid UTF-8")?;
+        Self::new(vault_root, relative)
+    }
+
+    pub fn as_path(&self) -> &Path {
+        &self.full_path
+    }
+
+    pub fn relative(&self) -> &Path {
+        &self.relative
+    }
+}
+
+impl AsRef<Path> for VaultPath {
+    fn as_ref(&self) -> &Path {
+        &self.full_path
+    }
+}
+
+/// Every markdown file in the vault, confined to it. Hidden folders and templates are skipped.
+pub fn list_markdown_files(vault_path: &Path) -> Result<Vec<VaultPath>, String> {
+    let pattern = vault_path.join("**/*.md");
+    let pattern_str = pattern.to_str().ok_or("Invalid vault path")?;
+
+    let mut files = Vec::new();
+    for entry in glob::glob(pattern_str).map_err(|e| e.to_string())? {
+        match entry.map_err(|e| e.to_string()).and_then(|path| VaultPath::from_full_path(vault_path, &path)) {
+            // Hidden folders hold app data (.trash, .secretariat) or other tools' config
+            Ok(note_path) if is_hidden(note_path.relative()) => {},
+            Ok(note_path) if note_path.relative().starts_with(TEMPLATES_DIR) => {},
+            Ok(note_path) => files.push(note_path),
+            Err(e) => println!("Skipping vault entry: {}", e),
+        }
+    }
+    Ok(files)
+}
+
+pub fn is_hidden(relative: &Path) -> bool {
+    relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// Frontmatter fields written by the app. The body lives outside of it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NoteMetadata {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(default)]
+    pub version: u64,
+    #[serde(default)]
+    pub device_id: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Fields added by templates or by hand (`type`, `aliases`, ...), kept as-is.
+    #[serde(flatten)]
+    pub extra: Mapping,
 }
 
 #[tauri::command]
@@ -30,65 +256,107 @@ pub fn create_note(
     state: State<'_, VaultState>,
     title: String,
     content: String,
-    tags: Vec<String>
+    tags: Vec<String>,
+    folder: Option<String>
 ) -> Result<String, String> {
     let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
     
-    // Robust sanitization (RUST-02)
-    let filename = sanitize_filename(&title);
-    let full_path = vault_path.join(&filename);
+    // Robust sanitization (RUST-02); a taken name gets a numbered suffix
+    let full_path = unique_note_path(&vault_path, folder.as_deref().unwrap_or(""), &title, None)?;
 
-    if full_path.exists() {
-        return Err("Note already exists".to_string());
+    if let Some(parent) = full_path.as_path().parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
+    let metadata = new_metadata(&title, tags);
+    save_note(&vault_path, &full_path, render_note(&metadata, &content)?)?;
 
-    let now = Utc::now().to_rfc3339();
-
-    // Create Metadata Struct for Frontmatter
-    #[derive(Serialize)]
-    struct NoteMetadata {
-        id: String,
-        title: String,
-        created_at: String,
-        updated_at: String,
-        version: u64,
-        device_id: String,
-        tags: Vec<String>,
-    }
+    Ok(full_path.relative().to_string_lossy().to_string())
+}
 
-    let metadata = NoteMetadata {
+/// Frontmatter for a note created right now on this device.
+pub fn new_metadata(title: &str, tags: Vec<String>) -> NoteMetadata {
+    let now = Utc::now().to_rfc3339();
+    NoteMetadata {
         id: uuid::Uuid::new_v4().to_string(),
-        title: title.clone(),
+        title: title.to_string(),
         created_at: now.clone(),
         updated_at: now,
         version: 1,
-        // TODO: Get actual device ID from config
-        device_id: "desktop-v1".to_string(), 
+        device_id: current_device_id(),
         tags,
-    };
+        extra: Mapping::new(),
+    }
+}
+
+/// Crash-safe write used by every vault mutation.
+/// The content goes to a temp file next to the target, is fsynced and then renamed
+/// over it, so readers only ever see the old or the new file, never a truncated one.
+pub fn save_file(path: &Path, content: impl AsRef<[u8]>) -> Result<(), String> {
+    let dir = path.parent().ok_or("Invalid file path")?;
+    let file_name = path.file_name().and_then(|n| n.to_str()).ok_or("Invalid file path")?;
+    let temp_path = dir.join(format!(".{}.{}{}", file_name, uuid::Uuid::new_v4(), TEMP_SUFFIX));
 
-    let yaml_frontmatter = serde_yaml::to_string(&metadata).map_err(|e| e.to_string())?;
-    let final_content = format!("---\n{}---\n\n{}", yaml_frontmatter, content);
+    let result = (|| -> std::io::Result<()> {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        sync_dir(dir)
+    })();
 
-    fs::write(&full_path, final_content).map_err(|e| e.to_string())?;
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result.map_err(|e| e.to_string())
+}
 
-    Ok(filename)
+/// Writes a note through `save_file`, first keeping the content it replaces
+/// in the note's revision history.
+pub fn save_note(vault_path: &Path, note_path: &VaultPath, content: impl AsRef<[u8]>) -> Result<(), String> {
+    snapshot_revision(vault_path, note_path)?;
+    save_file(note_path.as_path(), content)
+}
+
+/// Atomically writes a JSON sidecar file (indexes, state), creating its folder.
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    save_file(path, serde_json::to_string_pretty(value).map_err(|e| e.to_string())?)
 }
 
-/// Pure function for RUST-02 Test
-pub fn sanitize_filename(title: &str) -> String {
-    let re = regex::Regex::new(r"[<>:\|?*]").unwrap();
-    let safe_title = title.replace("/", "-").replace("\\", "-");
-    let safe_title = re.replace_all(&safe_title, "_");
-    format!("{}.md", safe_title)
+// The rename itself is only durable once the directory entry is flushed.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    fs::File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Startup recovery pass: removes temp files orphaned by a crash mid-write.
+/// The original file is untouched by an interrupted write, so dropping them is always safe.
+pub fn recover_vault(vault_path: &Path) -> Result<usize, String> {
+    let pattern = vault_path.join(format!("**/*{}", TEMP_SUFFIX));
+    let pattern_str = pattern.to_str().ok_or("Invalid vault path")?;
+
+    let mut removed = 0;
+    for path in glob::glob(pattern_str).map_err(|e| e.to_string())?.flatten() {
+        if fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
 }
 
 #[tauri::command]
 pub fn get_app_theme(state: State<'_, VaultState>) -> Result<String, String> {
     let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
-    let theme_path = vault_path.join("hack.css");
+    let theme_path = VaultPath::new(&vault_path, "hack.css")?;
 
-    if theme_path.exists() {
+    if theme_path.as_path().exists() {
         fs::read_to_string(theme_path).map_err(|e| e.to_string())
     } else {
         Ok("".to_string())
@@ -98,7 +366,523 @@ pub fn get_app_theme(state: State<'_, VaultState>) -> Result<String, String> {
 #[tauri::command]
 pub fn read_note(state: State<'_, VaultState>, filename: String) -> Result<String, String> {
     let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
-    let full_path = vault_path.join(filename);
+    let full_path = VaultPath::new(&vault_path, &filename)?;
+
+    fs::read_to_string(&full_path).map_err(|e| format!("{}: {}", full_path.relative().display(), e))
+}
+
+/// Scheme of links that keep pointing at a note after it is renamed or moved.
+pub const DEEP_LINK_PREFIX: &str = "secretariat://note/";
+/// Carries the `NoteDetails` of a link opened from outside the app.
+pub const OPEN_NOTE_EVENT: &str = "open-note";
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NoteDetails {
+    pub path: String, // Where the note currently lives
+    pub deep_link: String,
+    #[serde(flatten)]
+    pub note: Note,
+}
+
+#[tauri::command]
+pub fn get_note_by_id(state: State<'_, VaultState>, id: String) -> Result<NoteDetails, String> {
+    let (_, note_path) = state.find_note(&id)?;
+    let (metadata, content) = parse_note(&fs::read_to_string(&note_path).map_err(|e| e.to_string())?)?;
+
+    Ok(NoteDetails {
+        path: note_path.relative().to_string_lossy().to_string(),
+        deep_link: format!("{}{}", DEEP_LINK_PREFIX, metadata.id),
+        note: Note::new(metadata, content),
+    })
+}
+
+/// Opens a `secretariat://note/<uuid>` link.
+#[tauri::command]
+pub fn open_deep_link(state: State<'_, VaultState>, url: String) -> Result<NoteDetails, String> {
+    get_note_by_id(state, parse_deep_link(&url)?)
+}
+
+/// Resolves links the OS hands to the app and sends each note to the webview.
+pub fn route_deep_links(app: &AppHandle, urls: &[String]) {
+    for url in urls {
+        match parse_deep_link(url).and_then(|id| get_note_by_id(app.state::<VaultState>(), id)) {
+            Ok(details) => {
+                if let Err(e) = app.emit(OPEN_NOTE_EVENT, details) {
+                    println!("Failed to emit opened note: {}", e);
+                }
+            },
+            Err(e) => println!("Cannot open {}: {}", url, e),
+        }
+    }
+}
+
+pub fn parse_deep_link(url: &str) -> Result<String, String> {
+    let id = url
+        .trim()
+        .strip_prefix(DEEP_LINK_PREFIX)
+        .ok_or_else(|| format!("Not a note link: {}", url))?
+        .trim_end_matches('/');
+    uuid::Uuid::parse_str(id).map(|id| id.to_string()).map_err(|_| format!("Invalid note id in link: {}", url))
+}
+
+/// Saves an edit made in the editor.
+/// `expected_version` is the version the editor loaded; if the file on disk has
+/// moved on since (another window, sync, AI), the write is rejected.
+#[tauri::command]
+pub fn update_note(
+    state: State<'_, VaultState>,
+    filename: String,
+    expected_version: u64,
+    content: Option<String>,
+    tags: Option<Vec<String>>
+) -> Result<Note, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let full_path = VaultPath::new(&vault_path, &filename)?;
+
+    let raw = fs::read_to_string(&full_path).map_err(|e| e.to_string())?;
+    let mut document = Document::parse(&raw)?;
+    let mut metadata = document.metadata()?;
+
+    bump_version(&mut metadata, expected_version)?;
+    if let Some(tags) = tags {
+        metadata.tags = tags;
+    }
+    document.set_metadata(&metadata)?;
+    if let Some(content) = content {
+        document.set_content(&content);
+    }
+
+    save_note(&vault_path, &full_path, document.render()?)?;
+
+    Ok(Note::new(metadata, document.content().to_string()))
+}
+
+/// Optimistic concurrency check: only the version that was loaded may be replaced.
+/// On success the metadata describes the new revision written by this device.
+pub fn bump_version(metadata: &mut NoteMetadata, expected_version: u64) -> Result<(), String> {
+    if metadata.version != expected_version {
+        return Err(format!(
+            "Version conflict: note is at version {} but the editor loaded version {}",
+            metadata.version, expected_version
+        ));
+    }
+
+    touch_metadata(metadata);
+    Ok(())
+}
+
+/// Marks the metadata as a new revision written by this device, without a version check.
+/// Used for edits the app makes on its own (renames, link rewrites).
+pub fn touch_metadata(metadata: &mut NoteMetadata) {
+    metadata.version += 1;
+    metadata.updated_at = Utc::now().to_rfc3339();
+    metadata.device_id = current_device_id();
+}
+
+#[derive(Serialize, Debug)]
+pub struct RenameReport {
+    pub old_filename: String,
+    pub new_filename: String,
+    pub updated_files: Vec<String>,
+    pub links_rewritten: usize,
+}
+
+/// Renames a note and rewrites every inbound link so the graph keeps its edges.
+#[tauri::command]
+pub fn rename_note(
+    state: State<'_, VaultState>,
+    filename: String,
+    new_title: String
+) -> Result<RenameReport, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    rename_note_at(&vault_path, &filename, new_title)
+}
+
+pub fn rename_note_at(vault_path: &Path, filename: &str, new_title: String) -> Result<RenameReport, String> {
+    let old_path = VaultPath::new(vault_path, filename)?;
+
+    let folder = old_path.relative().parent().and_then(|p| p.to_str()).unwrap_or("").to_string();
+    let new_path = unique_note_path(vault_path, &folder, &new_title, Some(&old_path))?;
+    let new_relative = new_path.relative().to_string_lossy().to_string();
+
+    let raw = fs::read_to_string(&old_path).map_err(|e| e.to_string())?;
+    let mut document = Document::parse(&raw)?;
+    let mut metadata = document.metadata()?;
+
+    // Links may point at the file stem or at the frontmatter title, unless another
+    // note outranks this one for that name
+    let old_stem = old_path.as_path().file_stem().and_then(|s| s.to_str()).ok_or("Invalid note path")?.to_string();
+    let resolver = path_resolver(vault_path)?;
+    let old_relative = old_path.relative().to_string_lossy().replace('\\', "/");
+    let old_names: Vec<String> = [old_stem, metadata.title.clone()]
+        .into_iter()
+        .filter(|name| resolver.resolve(name) == Some(old_relative.as_str()))
+        .collect();
+
+    // Move first: a crash afterwards leaves stale links, never two copies of the note
+    fs::rename(&old_path, &new_path).map_err(|e| e.to_string())?;
+    metadata.title = new_title;
+    touch_metadata(&mut metadata);
+    document.set_metadata(&metadata)?;
+    save_note(vault_path, &new_path, document.render()?)?;
+
+    let (updated_files, links_rewritten) = relink_note(vault_path, &old_path, &new_path, &old_names)?;
+    Ok(RenameReport {
+        old_filename: filename.to_string(),
+        new_filename: new_relative,
+        updated_files,
+        links_rewritten,
+    })
+}
+
+/// Rewrites the links of every note after a note moved from `old_path` to
+/// `new_path`; see `relink_notes`. `old_names` are the file name or title links
+/// used for it before a rename, when they still resolve to it.
+pub fn relink_note(vault_path: &Path, old_path: &VaultPath, new_path: &VaultPath, old_names: &[String]) -> Result<(Vec<String>, usize), String> {
+    let relative = |path: &VaultPath| path.relative().to_string_lossy().replace('\\', "/");
+    relink_notes(vault_path, &[(relative(old_path), relative(new_path))], old_names)
+}
+
+/// Rewrites the links of every note, the moved ones included, after the notes in
+/// `moves` (vault-relative `(old, new)` paths) moved: wikilinks naming an old
+/// vault path, relative markdown links, and wikilinks naming one of `old_names`,
+/// which are only given for a single renamed note. A path stays a path, so it
+/// keeps telling clashing names apart. Returns the files changed and the links rewritten.
+pub fn relink_notes(vault_path: &Path, moves: &[(String, String)], old_names: &[String]) -> Result<(Vec<String>, usize), String> {
+    let name = |relative: &str| relative.strip_suffix(".md").unwrap_or(relative).to_string();
+    let renamed = match (moves, old_names) {
+        (_, []) => None,
+        ([(_, new_relative)], _) => Some(Path::new(new_relative).file_stem().and_then(|s| s.to_str()).ok_or("Invalid note path")?),
+        _ => return Err("Old names can only be rewritten for a single note".to_string()),
+    };
+
+    let mut updated_files = Vec::new();
+    let mut links_rewritten = 0;
+    for path in list_markdown_files(vault_path)? {
+        let Ok(content) = fs::read_to_string(&path) else { continue };
+
+        let Ok(mut document) = Document::parse(&content) else { continue };
+
+        let source = path.relative().to_string_lossy().replace('\\', "/");
+        let old_source = moves.iter().find(|(_, new)| *new == source).map_or(source.as_str(), |(old, _)| old.as_str());
+        let mut body = document.body().to_string();
+        let mut count = 0;
+        for (old, new) in moves {
+            let (rewritten, by_path) = rewrite_wikilinks(&body, &[name(old)], &name(new));
+            body = rewritten;
+            count += by_path;
+        }
+        if let Some(new_stem) = renamed {
+            let (rewritten, by_name) = rewrite_wikilinks(&body, old_names, new_stem);
+            body = rewritten;
+            count += by_name;
+        }
+        let (body, by_markdown) = rewrite_markdown_links(&body, old_source, &source, moves);
+        count += by_markdown;
+        if count == 0 {
+            continue;
+        }
+        document.set_body(body);
+        // Plain markdown without frontmatter is rewritten as-is
+        if let Ok(mut metadata) = document.metadata() {
+            touch_metadata(&mut metadata);
+            document.set_metadata(&metadata)?;
+        }
+
+        save_note(vault_path, &path, document.render()?)?;
+        updated_files.push(source);
+        links_rewritten += count;
+    }
+    Ok((updated_files, links_rewritten))
+}
+
+/// Resolves link names to vault-relative note paths, the way the graph does.
+fn path_resolver(vault_path: &Path) -> Result<LinkResolver, String> {
+    let mut files: Vec<String> = list_markdown_files(vault_path)?
+        .iter()
+        .map(|path| path.relative().to_string_lossy().replace('\\', "/"))
+        .collect();
+    files.sort(); // Same winner for clashing names as the graph
+    let mut resolver = LinkResolver::default();
+    for relative in &files {
+        let document = fs::read_to_string(vault_path.join(relative)).ok().and_then(|raw| Document::parse(&raw).ok());
+        let aliases = document.as_ref().map(frontmatter_aliases).unwrap_or_default();
+        let title = document.and_then(|document| document.metadata().ok()).map(|metadata| metadata.title);
+        resolver.add_note(relative, relative, title.as_deref(), &aliases);
+    }
+    Ok(resolver)
+}
+
+/// Returns today's (or `date`'s, `YYYY-MM-DD`) daily note, creating it if needed.
+#[tauri::command]
+pub fn open_daily_note(state: State<'_, VaultState>, date: Option<String>) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let date = parse_daily_date(date.as_deref())?;
+
+    let note_path = ensure_daily_note(&vault_path, date)?;
+    Ok(note_path.relative().to_string_lossy().to_string())
+}
+
+/// Adds a timestamped entry under `heading` (the configured default if omitted)
+/// in the daily note. Quick captures land here instead of becoming new notes.
+#[tauri::command]
+pub fn append_to_daily_note(
+    state: State<'_, VaultState>,
+    text: String,
+    heading: Option<String>,
+    date: Option<String>
+) -> Result<String, String> {
+    let vault_path = state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let date = parse_daily_date(date.as_deref())?;
+    let heading = heading.unwrap_or(load_config(&vault_path)?.daily_notes.default_heading);
+
+    let note_path = ensure_daily_note(&vault_path, date)?;
+    let raw = fs::read_to_string(&note_path).map_err(|e| e.to_string())?;
+    let mut document = Document::parse(&raw)?;
+    let mut metadata = document.metadata()?;
+
+    let entry = format!("- {} {}", Local::now().format("%H:%M"), text.trim().replace('\n', "\n  "));
+    let content = append_under_heading(document.content(), &heading, &entry);
+    document.set_content(&content);
+    touch_metadata(&mut metadata);
+    document.set_metadata(&metadata)?;
+    save_note(&vault_path, &note_path, document.render()?)?;
+
+    Ok(note_path.relative().to_string_lossy().to_string())
+}
+
+fn parse_daily_date(date: Option<&str>) -> Result<NaiveDate, String> {
+    match date {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| format!("Invalid date {}: {}", date, e)),
+        None => Ok(Local::now().date_naive()),
+    }
+}
+
+fn ensure_daily_note(vault_path: &Path, date: NaiveDate) -> Result<VaultPath, String> {
+    let config = load_config(vault_path)?.daily_notes;
+    let title = try_format(date.format(&config.date_format))
+        .ok_or_else(|| format!("Invalid daily note date format: {}", config.date_format))?;
+    let relative = Path::new(&config.folder).join(sanitize_filename(&title));
+    let note_path = VaultPath::new(vault_path, relative.to_str().ok_or("Invalid daily notes folder")?)?;
+
+    if note_path.as_path().exists() {
+        return Ok(note_path);
+    }
+
+    let mut metadata = new_metadata(&title, vec!["daily".to_string()]);
+    let body = match &config.template {
+        Some(template) => {
+            // Placeholders describe the day of the note, not the moment it is opened
+            let now = date.and_time(Local::now().time()).and_local_timezone(Local).earliest().unwrap_or_else(Local::now);
+            let rendered = instantiate_template(vault_path, template, &title, now)?;
+            apply_presets(&mut metadata, &rendered);
+            rendered.body
+        },
+        None => format!("# {}\n", title),
+    };
+
+    if let Some(parent) = note_path.as_path().parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    save_note(vault_path, &note_path, render_note(&metadata, &body)?)?;
+    Ok(note_path)
+}
+
+/// Formats a date with a user-supplied strftime pattern; `None` if the pattern is invalid.
+pub fn try_format(formatted: impl std::fmt::Display) -> Option<String> {
+    use std::fmt::Write as _;
+    let mut out = String::new();
+    write!(out, "{}", formatted).ok()?;
+    Some(out)
+}
+
+/// Inserts `entry` as the last line of the section under `heading`, creating the
+/// section at the end of the note if it does not exist yet.
+pub fn append_under_heading(body: &str, heading: &str, entry: &str) -> String {
+    let newline = if body.contains("\r\n") { "\r\n" } else { "\n" };
+
+    // The parser never reports headings inside code, whichever fence is used
+    let mut section: Option<(usize, HeadingLevel)> = None; // (End of the heading line, level)
+    let mut insert_at = body.len();
+    let mut current: Option<(Range<usize>, HeadingLevel, String)> = None;
+    for (event, range) in Parser::new_ext(body, Options::empty()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                if section.is_some_and(|(_, section_level)| level <= section_level) {
+                    insert_at = body[..range.start].rfind('\n').map_or(0, |at| at + 1);
+                    break;
+                }
+                current = Some((range, level, String::new()));
+            },
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, title)) = current.as_mut() {
+                    title.push_str(&text);
+                }
+            },
+            Event::End(TagEnd::Heading(_)) => {
+                let Some((range, level, title)) = current.take() else { continue };
+                if section.is_none() && title.trim().eq_ignore_ascii_case(heading.trim()) {
+                    let last = range.end.saturating_sub(1);
+                    section = Some((body[last..].find('\n').map_or(body.len(), |at| last + at + 1), level));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    let Some((heading_end, _)) = section else {
+        let blank_line = newline.repeat(2);
+        let separator = if body.is_empty() || body.ends_with(&blank_line) {
+            ""
+        } else if body.ends_with('\n') {
+            newline
+        } else {
+            &blank_line
+        };
+        return format!("{}{}## {}{}{}{}", body, separator, heading.trim(), newline, entry, newline);
+    };
+
+    // Keep blank lines that close the section after the new entry
+    let lines: Vec<&str> = body[heading_end..insert_at].split_inclusive('\n').collect();
+    let kept = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(0, |last| last + 1);
+    let at = heading_end + lines[..kept].iter().map(|line| line.len()).sum::<usize>();
+    let lead = if at > 0 && !body[..at].ends_with('\n') { newline } else { "" };
+    format!("{}{}{}{}{}", &body[..at], lead, entry, newline, &body[at..])
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NoteSummary {
+    pub id: Option<String>, // None for markdown without frontmatter
+    pub title: String,
+    pub path: String,
+    pub tags: Vec<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub word_count: usize,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteSort {
+    Title,
+    #[default]
+    UpdatedAt,
+    CreatedAt,
+    Path,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ListNotesQuery {
+    #[serde(default)]
+    pub sort_by: NoteSort,
+    #[serde(default)]
+    pub descending: bool,
+    pub tag: Option<String>,
+    pub folder: Option<String>,
+    pub cursor: Option<String>, // `next_cursor` of the previous page
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NotePage {
+    pub notes: Vec<NoteSummary>,
+    pub next_cursor: Option<String>,
+    pub total: usize, // Matching notes across all pages
+}
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Sidebar listing: frontmatter of every note, filtered, sorted and paginated.
+/// Summaries come from the graph index, so only files changed since are read.
+#[tauri::command]
+pub async fn list_notes(
+    app: AppHandle,
+    vault_state: State<'_, VaultState>,
+    graph_state: State<'_, GraphState>,
+    query: Option<ListNotesQuery>
+) -> Result<NotePage, String> {
+    let vault_path = vault_state.vault_path.lock().map_err(|e| e.to_string())?.clone();
+    let _store = sync_index(&app, &vault_path, &graph_state).await?;
+    let summaries: Vec<NoteSummary> = graph_state.cache.lock().map_err(|e| e.to_string())?
+        .files.iter()
+        .map(|(relative, entry)| entry.summary(relative))
+        .collect();
+
+    paginate_notes(summaries, &query.unwrap_or_default())
+}
+
+/// Filters, sorts and cuts one page out of `summaries`.
+/// The cursor encodes the sort key and path of the last note returned, so pages stay
+/// stable when notes are created or deleted in between requests.
+pub fn paginate_notes(summaries: Vec<NoteSummary>, query: &ListNotesQuery) -> Result<NotePage, String> {
+    let tag = query.tag.as_deref().map(normalize_tag);
+    let folder = query.folder.as_deref().map(Path::new).filter(|f| !f.as_os_str().is_empty());
+
+    let mut keyed: Vec<(String, NoteSummary)> = summaries
+        .into_iter()
+        .filter(|note| tag.as_ref().is_none_or(|tag| note.tags.iter().any(|t| normalize_tag(t) == *tag)))
+        .filter(|note| folder.is_none_or(|folder| Path::new(&note.path).starts_with(folder)))
+        .map(|note| (sort_key(&note, query.sort_by), note))
+        .collect();
+
+    keyed.sort_by(|(a_key, a), (b_key, b)| a_key.cmp(b_key).then_with(|| a.path.cmp(&b.path)));
+    if query.descending {
+        keyed.reverse();
+    }
+    let total = keyed.len();
+
+    let start = match &query.cursor {
+        Some(cursor) => {
+            let (cursor_key, cursor_path): (String, String) =
+                serde_json::from_str(cursor).map_err(|_| "Invalid cursor".to_string())?;
+            let cursor = (cursor_key.as_str(), cursor_path.as_str());
+            keyed
+                .iter()
+                .position(|(key, note)| {
+                    let position = (key.as_str(), note.path.as_str());
+                    if query.descending { position < cursor } else { position > cursor }
+                })
+                .unwrap_or(total)
+        },
+        None => 0,
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let page: Vec<(String, NoteSummary)> = keyed.into_iter().skip(start).take(limit).collect();
+    let next_cursor = if start + page.len() < total {
+        page.last().map(|(key, note)| serde_json::json!([key, note.path]).to_string())
+    } else {
+        None
+    };
+
+    Ok(NotePage {
+        notes: page.into_iter().map(|(_, note)| note).collect(),
+        next_cursor,
+        total,
+    })
+}
+
+fn sort_key(note: &NoteSummary, sort_by: NoteSort) -> String {
+    match sort_by {
+        NoteSort::Title => note.title.to_lowercase(),
+        NoteSort::UpdatedAt => note.updated_at.clone().or_else(|| note.created_at.clone()).unwrap_or_default(),
+        NoteSort::CreatedAt => note.created_at.clone().unwrap_or_default(),
+        NoteSort::Path => note.path.to_lowercase(),
+    }
+}
+
+/// `#Work` and `work` are the same tag.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Parses a note into its metadata and the body the editor shows.
+pub fn parse_note(raw: &str) -> Result<(NoteMetadata, String), String> {
+    let document = Document::parse(raw)?;
+    Ok((document.metadata()?, document.content().to_string()))
+}
 
-    fs::read_to_string(full_path).map_err(|e| e.to_string())
+/// Renders a new note. Existing files are edited through `Document` so their layout survives.
+pub fn render_note(metadata: &NoteMetadata, body: &str) -> Result<String, String> {
+    Document::new_note(metadata, body)?.render()
 }
@@ -0,0 +1,130 @@
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use chrono::Utc;
+use crate::graph_engine::GraphState;
+use crate::migration_manager::SCHEMA_VERSION;
+use crate::vault_config::{save_config, VaultConfig};
+use crate::vault_manager::{recover_vault, save_json, NoteIndex, VaultState};
+use crate::vault_watcher::WatcherState;
+
+/// Recently opened vaults, kept in the app data dir since it spans vaults.
+const REGISTRY_FILE: &str = "vaults.json";
+const MAX_RECENT_VAULTS: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecentVault {
+    pub path: String,
+    pub name: String,
+    pub last_opened: String, // ISO 8601
+    #[serde(skip_deserializing)]
+    pub exists: bool, // Folder may have been moved or deleted since
+}
+
+/// Most recently opened first.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct VaultRegistry {
+    pub recent: Vec<RecentVault>,
+}
+
+/// Opens an existing folder as a vault and makes it the active one.
+#[tauri::command]
+pub fn open_vault(app: AppHandle, path: String) -> Result<RecentVault, String> {
+    let vault_path = PathBuf::from(&path);
+    if !vault_path.is_dir() {
+        return Err(format!("Not a folder: {}", path));
+    }
+    activate_vault(&app, &vault_path)
+}
+
+/// Creates a new, empty vault with default settings and switches to it.
+#[tauri::command]
+pub fn create_vault(app: AppHandle, path: String) -> Result<RecentVault, String> {
+    let vault_path = PathBuf::from(&path);
+    if vault_path.exists() && fs::read_dir(&vault_path).map_err(|e| e.to_string())?.next().is_some() {
+        return Err(format!("Folder is not empty: {}", path));
+    }
+    fs::create_dir_all(&vault_path).map_err(|e| e.to_string())?;
+    // Nothing to migrate in an empty vault
+    save_config(&vault_path, &VaultConfig { schema_version: SCHEMA_VERSION, ..VaultConfig::default() })?;
+    activate_vault(&app, &vault_path)
+}
+
+#[tauri::command]
+pub fn list_recent_vaults(app: AppHandle) -> Result<Vec<RecentVault>, String> {
+    let mut recent = load_registry(&registry_file(&app)?)?.recent;
+    for vault in &mut recent {
+        vault.exists = Path::new(&vault.path).is_dir();
+    }
+    Ok(recent)
+}
+
+/// Switches to a vault from the recent list.
+#[tauri::command]
+pub fn switch_vault(app: AppHandle, path: String) -> Result<RecentVault, String> {
+    let registry = load_registry(&registry_file(&app)?)?;
+    if !registry.recent.iter().any(|vault| vault.path == path) {
+        return Err(format!("Unknown vault: {}", path));
+    }
+    open_vault(app, path)
+}
+
+/// The vault to open at startup: the last one used, if it still exists.
+pub fn last_vault(app: &AppHandle) -> Option<PathBuf> {
+    let registry = load_registry(&registry_file(app).ok()?).ok()?;
+    registry.recent.into_iter().map(|vault| PathBuf::from(vault.path)).find(|path| path.is_dir())
+}
+
+/// Points every piece of per-vault state at `vault_path`. Caches built from
+/// the previous vault are dropped and its watcher is replaced.
+pub fn activate_vault(app: &AppHandle, vault_path: &Path) -> Result<RecentVault, String> {
+    let vault_path = fs::canonicalize(vault_path).map_err(|e| e.to_string())?;
+
+    match recover_vault(&vault_path) {
+        Ok(0) => {},
+        Ok(removed) => println!("Recovered vault: removed {} interrupted writes", removed),
+        Err(e) => println!("Vault recovery failed: {}", e),
+    }
+
+    let vault_state = app.state::<VaultState>();
+    *vault_state.vault_path.lock().map_err(|e| e.to_string())? = vault_path.clone();
+    *vault_state.note_index.lock().map_err(|e| e.to_string())? = NoteIndex::default();
+    *app.state::<GraphState>().last_data.lock().map_err(|e| e.to_string())? = None;
+    if let Err(e) = app.state::<WatcherState>().watch(app, &vault_path) {
+        println!("Vault watcher failed to start: {}", e);
+    }
+    println!("Switched to vault: {}", vault_path.display());
+
+    remember_vault(&registry_file(app)?, &vault_path)
+}
+
+/// Moves `vault_path` to the top of the recent list.
+pub fn remember_vault(registry_path: &Path, vault_path: &Path) -> Result<RecentVault, String> {
+    let mut registry = load_registry(registry_path)?;
+    let path = vault_path.to_string_lossy().to_string();
+
+    let entry = RecentVault {
+        name: vault_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.clone()),
+        path,
+        last_opened: Utc::now().to_rfc3339(),
+        exists: true,
+    };
+    registry.recent.retain(|vault| vault.path != entry.path);
+    registry.recent.insert(0, entry.clone());
+    registry.recent.truncate(MAX_RECENT_VAULTS);
+
+    save_json(registry_path, &registry)?;
+    Ok(entry)
+}
+
+pub fn load_registry(registry_path: &Path) -> Result<VaultRegistry, String> {
+    match fs::read_to_string(registry_path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        Err(_) => Ok(VaultRegistry::default()),
+    }
+}
+
+fn registry_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join(REGISTRY_FILE))
+}
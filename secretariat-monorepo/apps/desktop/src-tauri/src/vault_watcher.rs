@@ -0,0 +1,155 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, FileIdMap};
+use crate::graph_engine::GraphState;
+use crate::vault_manager::{is_hidden, VaultState};
+
+/// Event the frontend listens to; the payload is a `Vec<VaultChange>`.
+pub const VAULT_CHANGED_EVENT: &str = "vault-changed";
+
+/// Editors save in bursts (temp file, rename, chmod); one batch per burst is enough.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct VaultChange {
+    pub kind: ChangeKind,
+    pub path: String, // Vault-relative
+    pub from: Option<String>, // Previous path of a rename
+}
+
+/// Keeps the active watcher alive; dropping it stops watching.
+pub struct WatcherState {
+    pub debouncer: Mutex<Option<Debouncer<RecommendedWatcher, FileIdMap>>>,
+}
+
+impl WatcherState {
+    pub fn new() -> Self {
+        Self {
+            debouncer: Mutex::new(None),
+        }
+    }
+
+    /// Starts watching `vault_path`, replacing any previous watcher. On failure
+    /// nothing is watched, rather than the previous vault.
+    pub fn watch(&self, app: &AppHandle, vault_path: &Path) -> Result<(), String> {
+        let mut current = self.debouncer.lock().map_err(|e| e.to_string())?;
+        *current = None;
+        *current = Some(start_watcher(app.clone(), vault_path.to_path_buf())?);
+        Ok(())
+    }
+
+    /// Whether changes to the active vault are being picked up.
+    pub fn is_active(&self) -> bool {
+        self.debouncer.lock().map(|debouncer| debouncer.is_some()).unwrap_or(false)
+    }
+}
+
+fn start_watcher(app: AppHandle, vault_path: PathBuf) -> Result<Debouncer<RecommendedWatcher, FileIdMap>, String> {
+    // Backends may report canonical paths (e.g. /private/var on macOS)
+    let canonical = fs::canonicalize(&vault_path).unwrap_or_else(|_| vault_path.clone());
+    let roots = vec![vault_path.clone(), canonical.clone()];
+
+    let mut debouncer = new_debouncer(DEBOUNCE, None, move |result: DebounceEventResult| match result {
+        Ok(events) => {
+            let events: Vec<Event> = events.into_iter().map(|e| e.event).collect();
+            let changes = classify_events(&roots, &events);
+            if !changes.is_empty() {
+                apply_changes(&app, &canonical, &changes);
+            }
+        },
+        Err(errors) => {
+            for e in errors {
+                println!("Vault watcher error: {}", e);
+            }
+        },
+    })
+    .map_err(|e| e.to_string())?;
+
+    debouncer.watcher().watch(&vault_path, RecursiveMode::Recursive).map_err(|e| e.to_string())?;
+    // File ids let the debouncer pair up the two halves of a rename
+    debouncer.cache().add_root(&vault_path, RecursiveMode::Recursive);
+    println!("Watching vault at: {}", vault_path.display());
+    Ok(debouncer)
+}
+
+/// Drops caches built from the old files, refreshes the id index and tells the frontend what changed.
+fn apply_changes(app: &AppHandle, vault_path: &Path, changes: &[VaultChange]) {
+    if let Ok(mut last_data) = app.state::<GraphState>().last_data.lock() {
+        *last_data = None;
+    }
+    if let Ok(mut note_index) = app.state::<VaultState>().note_index.lock() {
+        for change in changes {
+            if let Some(from) = &change.from {
+                note_index.remove(from);
+            }
+            match change.kind {
+                ChangeKind::Removed => note_index.remove(&change.path),
+                _ => note_index.update(vault_path, &change.path),
+            }
+        }
+    }
+    if let Err(e) = app.emit(VAULT_CHANGED_EVENT, changes.to_vec()) {
+        println!("Failed to emit vault change: {}", e);
+    }
+}
+
+/// Turns raw filesystem events into vault changes. App data (`.secretariat`,
+/// `.trash`), in-flight temp files and paths outside of `roots` are dropped.
+pub fn classify_events(roots: &[PathBuf], events: &[Event]) -> Vec<VaultChange> {
+    let relative = |path: &Path| -> Option<String> {
+        let relative = roots.iter().find_map(|root| path.strip_prefix(root).ok())?;
+        if relative.as_os_str().is_empty() || is_hidden(relative) {
+            return None;
+        }
+        Some(relative.to_string_lossy().to_string())
+    };
+
+    let mut changes: Vec<VaultChange> = Vec::new();
+    for event in events {
+        let kind = match event.kind {
+            EventKind::Create(_) => ChangeKind::Created,
+            EventKind::Remove(_) => ChangeKind::Removed,
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                let [from, to] = &event.paths[..] else { continue };
+                // A rename into or out of a hidden folder is a delete or a create for the vault
+                let change = match (relative(from), relative(to)) {
+                    (Some(from), Some(to)) => VaultChange { kind: ChangeKind::Renamed, path: to, from: Some(from) },
+                    (Some(from), None) => VaultChange { kind: ChangeKind::Removed, path: from, from: None },
+                    (None, Some(to)) => VaultChange { kind: ChangeKind::Created, path: to, from: None },
+                    (None, None) => continue,
+                };
+                changes.push(change);
+                continue;
+            },
+            // Unpaired halves of a rename
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => ChangeKind::Removed,
+            EventKind::Modify(ModifyKind::Name(_)) => ChangeKind::Created,
+            EventKind::Modify(ModifyKind::Metadata(_)) => continue,
+            EventKind::Modify(_) => ChangeKind::Modified,
+            _ => continue,
+        };
+
+        for path in event.paths.iter().filter_map(|p| relative(p)) {
+            let change = VaultChange { kind, path, from: None };
+            if !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+    }
+    changes
+}
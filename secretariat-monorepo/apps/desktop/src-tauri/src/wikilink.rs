@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag};
+use regex::Regex;
+use serde_yaml::Value;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use crate::frontmatter::Document;
+
+/// One `[[...]]` link, split into its parts:
+/// `[[target#Heading|alias]]`, `[[target#^block-id]]`, `[[#Heading]]` (same note).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WikiLink {
+    pub target: String, // Empty for a link into the same note
+    pub heading: Option<String>, // Nested headings stay joined: `A#B`
+    pub block: Option<String>, // Without the `^`
+    pub alias: Option<String>,
+}
+
+impl WikiLink {
+    /// Parses the text between `[[` and `]]`. A `\|` (written inside tables) counts as `|`.
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.replace("\\|", "|");
+        let (reference, alias) = match raw.split_once('|') {
+            Some((reference, alias)) => (reference, Some(alias.trim().to_string())),
+            None => (raw.as_str(), None),
+        };
+        let (target, anchor) = match reference.split_once('#') {
+            Some((target, anchor)) => (target, Some(anchor.trim())),
+            None => (reference, None),
+        };
+
+        let mut link = WikiLink {
+            target: target.trim().to_string(),
+            alias: alias.filter(|alias| !alias.is_empty()),
+            ..WikiLink::default()
+        };
+        match anchor {
+            Some(anchor) if anchor.starts_with('^') => link.block = Some(anchor[1..].to_string()),
+            Some(anchor) if !anchor.is_empty() => link.heading = Some(anchor.to_string()),
+            _ => {},
+        }
+        link
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkSyntax {
+    Wiki, // `[[Note]]`
+    Markdown, // `[text](Note.md)`, relative to the linking note
+}
+
+/// A link found in a note. `raw` is in wikilink grammar either way: markdown
+/// destinations are percent-decoded, so `Other%20Note.md#Plan` becomes `Other Note.md#Plan`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteLink {
+    pub raw: String,
+    pub syntax: LinkSyntax,
+    pub embed: bool, // `![[...]]` transclusion or `![alt](...)` image
+}
+
+impl NoteLink {
+    /// The parsed link, with a markdown path made vault-relative from `source`,
+    /// the vault-relative path of the note it was found in.
+    pub fn resolve_from(&self, source: &str) -> WikiLink {
+        let mut link = WikiLink::parse(&self.raw);
+        if self.syntax == LinkSyntax::Markdown && !link.target.is_empty() {
+            let folder = Path::new(source).parent().unwrap_or(Path::new(""));
+            link.target = join_relative(folder, &link.target);
+        }
+        link
+    }
+}
+
+/// Every link in a markdown note, in document order. Code spans and fenced code
+/// blocks are skipped, so shell tests like `[[ -f x ]]` are not links.
+pub fn parse_note_links(content: &str) -> Vec<NoteLink> {
+    note_link_spans(content).into_iter().map(|(_, link)| link).collect()
+}
+
+/// `parse_note_links` with where each target is written: the text inside `[[...]]`,
+/// or an inline markdown destination. Reference-style markdown links have no
+/// range, as their destination sits in a shared definition.
+pub fn note_link_spans(content: &str) -> Vec<(Option<Range<usize>>, NoteLink)> {
+    let mut code: Vec<Range<usize>> = Vec::new();
+    let mut links: Vec<(usize, Option<Range<usize>>, NoteLink)> = Vec::new();
+
+    for (event, range) in Parser::new_ext(content, Options::empty()).into_offset_iter() {
+        match event {
+            // Only fenced blocks: four-space indented text is far more often a
+            // pasted or nested paragraph than code
+            Event::Code(_) | Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(_))) => code.push(range),
+            Event::Start(Tag::Link { link_type, dest_url, .. }) | Event::Start(Tag::Image { link_type, dest_url, .. }) => {
+                if matches!(link_type, LinkType::Autolink | LinkType::Email) || !is_relative_url(&dest_url) {
+                    continue;
+                }
+                let raw = urlencoding::decode(&dest_url).map(|s| s.into_owned()).unwrap_or_else(|_| dest_url.to_string());
+                let embed = content[range.clone()].starts_with('!');
+                // The destination comes last in `[text](dest "title")`
+                let span = (link_type == LinkType::Inline)
+                    .then(|| content[range.clone()].rfind(&*dest_url))
+                    .flatten()
+                    .map(|at| range.start + at..range.start + at + dest_url.len());
+                links.push((range.start, span, NoteLink { raw, syntax: LinkSyntax::Markdown, embed }));
+            },
+            _ => {},
+        }
+    }
+
+    let wikilink = Regex::new(r"(!?)\[\[(.*?)\]\]").unwrap();
+    for cap in wikilink.captures_iter(content) {
+        let (Some(whole), Some(inner)) = (cap.get(0), cap.get(2)) else { continue };
+        if code.iter().any(|range| range.contains(&whole.start())) {
+            continue;
+        }
+        let embed = !cap[1].is_empty();
+        links.push((whole.start(), Some(inner.range()), NoteLink { raw: inner.as_str().to_string(), syntax: LinkSyntax::Wiki, embed }));
+    }
+
+    links.sort_by_key(|(start, _, _)| *start);
+    links.into_iter().map(|(_, span, link)| (span, link)).collect()
+}
+
+/// Links to other files, not web pages (`https:`), mail or anchors in the same note.
+fn is_relative_url(url: &str) -> bool {
+    if url.is_empty() || url.starts_with('#') || url.starts_with('/') {
+        return false;
+    }
+    // A scheme is letters followed by `:` before any path separator
+    match url.split_once(':') {
+        Some((scheme, _)) => scheme.contains('/') || !scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)),
+        None => true,
+    }
+}
+
+/// `folder/target` with `.` and `..` applied; never climbs above the vault root.
+fn join_relative(folder: &Path, target: &str) -> String {
+    let mut path = PathBuf::new();
+    for component in folder.join(target).components() {
+        match component {
+            Component::ParentDir => {
+                path.pop();
+            },
+            Component::Normal(part) => path.push(part),
+            _ => {},
+        }
+    }
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// The form names are compared in: without accents or case, with runs of
+/// whitespace collapsed and no `.md` extension. "Café  Notes" == "cafe notes".
+pub fn normalize_name(name: &str) -> String {
+    let stripped: String = name.nfd().filter(|c| !is_combining_mark(*c)).collect();
+    let name = stripped.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    name.strip_suffix(".md").map(str::to_string).unwrap_or(name)
+}
+
+/// The `aliases:` (or `alias:`) frontmatter of a note, as a list or a single string.
+pub fn frontmatter_aliases(document: &Document) -> Vec<String> {
+    let value = document.get("aliases").or_else(|| document.get("alias"));
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect(),
+        Some(Value::String(alias)) => vec![alias.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Maps every name a link may use for a note to the note's id.
+/// When two notes claim a name, a vault path beats a file name, which beats a
+/// title, which beats an alias; among equals the first note added wins.
+#[derive(Default)]
+pub struct LinkResolver {
+    names: HashMap<String, (u8, String)>, // Normalized name -> (rank, id)
+}
+
+impl LinkResolver {
+    /// `relative` is the vault-relative path of the note file.
+    pub fn add_note(&mut self, relative: &str, id: &str, title: Option<&str>, aliases: &[String]) {
+        let path = relative.replace('\\', "/");
+        let stem = path.rsplit('/').next().unwrap_or_default();
+        self.add(0, &path, id);
+        self.add(1, stem, id);
+        if let Some(title) = title {
+            self.add(2, title, id);
+        }
+        for alias in aliases {
+            self.add(3, alias, id);
+        }
+    }
+
+    fn add(&mut self, rank: u8, name: &str, id: &str) {
+        let key = normalize_name(name);
+        if key.is_empty() {
+            return;
+        }
+        match self.names.get(&key) {
+            Some((existing, _)) if *existing <= rank => {},
+            _ => {
+                self.names.insert(key, (rank, id.to_string()));
+            },
+        }
+    }
+
+    /// The id of the note `target` names, if any.
+    pub fn resolve(&self, target: &str) -> Option<&str> {
+        self.names.get(&normalize_name(target)).map(|(_, id)| id.as_str())
+    }
+}
@@ -34,7 +34,7 @@ export function GraphView() {
 
     useEffect(() => {
         // Initial fetch of "The Lobotomy" data (Topology only)
-        invoke<GraphData>("rebuild_graph")
+        invoke<GraphData>("get_graph")
             .then((graphData) => {
                 setData(graphData);
                 setIsLoading(false);